@@ -4,9 +4,26 @@ use std::{error::Error, fmt, str::FromStr};
 use clap::{builder::PossibleValuesParser, Args, Parser, Subcommand};
 
 use crate::{
-    commands::{exec::ExecCommand, ps::PsCommand, start::StartCommand, stop::StopCommand},
+    backup_commands::{
+        create::BackupCreateCommand, ls::BackupLsCommand, restore::BackupRestoreCommand,
+        rm::BackupRmCommand,
+    },
+    certs_commands::{init::InitCommand, issue::IssueCommand},
+    commands::{
+        cp::CpCommand, dns::DnsCommand, down::DownCommand, env::EnvCommand, exec::ExecCommand,
+        foreach::ForeachCommand, help::HelpCommand, proxy::ProxyCommand,
+        proxy_config::ProxyConfigCommand, ps::PsCommand, r#do::DoCommand, run::RunCommand,
+        run_once::RunOnceCommand, shell::ShellCommand, start::StartCommand, stop::StopCommand,
+        top::TopCommand, urls::UrlsCommand, watch::WatchCommand,
+    },
     core::{GlobalOptions, Mode, MODE_VALUES},
+    hosts_commands::{clean::CleanCommand, sync::SyncCommand},
+    network_commands::{ls::NetworkLsCommand, prune::NetworkPruneCommand},
     project_commands::{add::AddCommand, r#use::UseCommand, ls::LsCommand, rm::RmCommand},
+    session_commands::{
+        ls::SessionLsCommand, restore::SessionRestoreCommand, save::SessionSaveCommand,
+    },
+    volume_commands::{ls::VolumeLsCommand, rm::VolumeRmCommand},
 };
 
 pub trait ExecuteTrait {
@@ -14,7 +31,13 @@ pub trait ExecuteTrait {
 }
 
 #[derive(Debug, Parser)]
-#[clap(author, version, about, arg_required_else_help = true)]
+#[clap(
+    author,
+    version,
+    about,
+    arg_required_else_help = true,
+    disable_help_subcommand = true
+)]
 pub struct AppArgs {
     #[arg(short, long)]
     pub name: Option<String>,
@@ -40,6 +63,10 @@ pub struct AppArgs {
     #[arg(long)]
     pub no_tty: bool,
 
+    /// Print machine-readable JSON where supported
+    #[arg(long)]
+    pub json: bool,
+
     #[clap(subcommand)]
     pub command: CommandType,
 }
@@ -58,13 +85,136 @@ pub struct ProjectCommand {
     command: ProjectCommandType,
 }
 
+#[derive(Debug, Subcommand)]
+pub enum CertsCommandType {
+    /// Create a workspace-local certificate authority
+    Init(InitCommand),
+    /// Issue a certificate for all component hostnames
+    Issue(IssueCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct CertsCommand {
+    #[clap(subcommand)]
+    command: CertsCommandType,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum HostsCommandType {
+    /// Write every component hostname into the hosts file
+    Sync(SyncCommand),
+    /// Remove the workspace entries from the hosts file
+    Clean(CleanCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct HostsCommand {
+    #[clap(subcommand)]
+    command: HostsCommandType,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NetworkCommandType {
+    /// List networks of the workspace
+    Ls(NetworkLsCommand),
+    /// Remove networks created for the workspace that are no longer declared
+    Prune(NetworkPruneCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct NetworkCommand {
+    #[clap(subcommand)]
+    command: NetworkCommandType,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum VolumeCommandType {
+    /// List volumes of the workspace and the components they belong to
+    Ls(VolumeLsCommand),
+    /// Remove volumes of the workspace
+    Rm(VolumeRmCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct VolumeCommand {
+    #[clap(subcommand)]
+    command: VolumeCommandType,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BackupCommandType {
+    /// Dump the data of a component into a new archive
+    Create(BackupCreateCommand),
+    /// Load an archive back into a component
+    Restore(BackupRestoreCommand),
+    /// List archives of the workspace
+    Ls(BackupLsCommand),
+    /// Remove archives of a component
+    Rm(BackupRmCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct BackupCommand {
+    #[clap(subcommand)]
+    command: BackupCommandType,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SessionCommandType {
+    /// Remember which components are running, in which modes and on which branches
+    Save(SessionSaveCommand),
+    /// Bring up the components of a session and stop the others
+    Restore(SessionRestoreCommand),
+    /// List saved sessions
+    Ls(SessionLsCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct SessionCommand {
+    #[clap(subcommand)]
+    command: SessionCommandType,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum CommandType {
+    #[command(alias = "up")]
     Start(StartCommand),
     Stop(StopCommand),
+    Down(DownCommand),
     Exec(ExecCommand),
+    /// Run a script of the workspace
+    Run(RunCommand),
+    /// Run a command in a throwaway container of a component
+    RunOnce(RunOnceCommand),
+    /// Copy files between the host and a component container
+    Cp(CpCommand),
+    /// Restart components or run commands when their files change
+    Watch(WatchCommand),
+    /// Start $SHELL with the workspace or component context exported
+    Shell(ShellCommand),
+    /// Print the workspace or component context
+    Env(EnvCommand),
+    /// Run a host command in the directory of every component and package
+    Foreach(ForeachCommand),
+    /// Run a custom command of a component, same as `uniform <component> <command>`
+    Do(DoCommand),
+    /// Print help of a command, or list the custom commands of a component
+    Help(HelpCommand),
     Ps(PsCommand),
+    Top(TopCommand),
+    Urls(UrlsCommand),
+    Proxy(ProxyCommand),
+    Dns(DnsCommand),
+    ProxyConfig(ProxyConfigCommand),
+    Certs(CertsCommand),
+    Hosts(HostsCommand),
+    Network(NetworkCommand),
+    Volume(VolumeCommand),
+    Backup(BackupCommand),
+    Session(SessionCommand),
     Project(ProjectCommand),
+    #[command(external_subcommand)]
+    External(Vec<String>),
 }
 
 impl CommandType {
@@ -75,8 +225,53 @@ impl CommandType {
         let cmd: &dyn ExecuteTrait = match self {
             CommandType::Start(cmd) => cmd,
             CommandType::Stop(cmd) => cmd,
+            CommandType::Down(cmd) => cmd,
             CommandType::Exec(cmd) => cmd,
+            CommandType::Run(cmd) => cmd,
+            CommandType::RunOnce(cmd) => cmd,
+            CommandType::Cp(cmd) => cmd,
+            CommandType::Watch(cmd) => cmd,
+            CommandType::Shell(cmd) => cmd,
+            CommandType::Env(cmd) => cmd,
+            CommandType::Foreach(cmd) => cmd,
+            CommandType::Do(cmd) => cmd,
+            CommandType::Help(cmd) => cmd,
+            CommandType::External(args) => {
+                return DoCommand::external(args)?.execute(global_options)
+            }
             CommandType::Ps(cmd) => cmd,
+            CommandType::Top(cmd) => cmd,
+            CommandType::Urls(cmd) => cmd,
+            CommandType::Proxy(cmd) => cmd,
+            CommandType::Dns(cmd) => cmd,
+            CommandType::ProxyConfig(cmd) => cmd,
+            CommandType::Certs(cmd) => match &cmd.command {
+                CertsCommandType::Init(certs_cmd) => certs_cmd,
+                CertsCommandType::Issue(certs_cmd) => certs_cmd,
+            },
+            CommandType::Hosts(cmd) => match &cmd.command {
+                HostsCommandType::Sync(hosts_cmd) => hosts_cmd,
+                HostsCommandType::Clean(hosts_cmd) => hosts_cmd,
+            },
+            CommandType::Network(cmd) => match &cmd.command {
+                NetworkCommandType::Ls(network_cmd) => network_cmd,
+                NetworkCommandType::Prune(network_cmd) => network_cmd,
+            },
+            CommandType::Volume(cmd) => match &cmd.command {
+                VolumeCommandType::Ls(volume_cmd) => volume_cmd,
+                VolumeCommandType::Rm(volume_cmd) => volume_cmd,
+            },
+            CommandType::Backup(cmd) => match &cmd.command {
+                BackupCommandType::Create(backup_cmd) => backup_cmd,
+                BackupCommandType::Restore(backup_cmd) => backup_cmd,
+                BackupCommandType::Ls(backup_cmd) => backup_cmd,
+                BackupCommandType::Rm(backup_cmd) => backup_cmd,
+            },
+            CommandType::Session(cmd) => match &cmd.command {
+                SessionCommandType::Save(session_cmd) => session_cmd,
+                SessionCommandType::Restore(session_cmd) => session_cmd,
+                SessionCommandType::Ls(session_cmd) => session_cmd,
+            },
             CommandType::Project(cmd) => match &cmd.command {
                 ProjectCommandType::Add(project_cmd) => project_cmd,
                 ProjectCommandType::Use(project_cmd) => project_cmd,
@@ -100,10 +295,13 @@ pub fn parse_global_options(args: &AppArgs) -> GlobalOptions {
             Ok(val) => Some(val),
         },
         working_dir: None,
+        service: None,
+        env: None,
         uid: args.uid,
         tag: args.tag.clone(),
         dry_run: args.dry_run,
         no_tty: args.no_tty,
+        json: args.json,
     }
 }
 
@@ -0,0 +1,608 @@
+use std::error::Error;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::{
+    args::CliError,
+    component::Component,
+    core::{
+        docker_program, exec_shell_piped, shell_quote, subst_known_vars, subst_vars, validate_name,
+        GlobalOptions,
+    },
+    status::WorkspaceStatus,
+    workspace::Workspace,
+};
+
+const POSTGRES_DUMP: &str = "pg_dumpall --clean --if-exists -U \"${POSTGRES_USER:-postgres}\"";
+const POSTGRES_RESTORE: &str = "psql -q -U \"${POSTGRES_USER:-postgres}\" -d postgres";
+const MYSQL_DUMP: &str = "mysqldump --all-databases -uroot -p\"$MYSQL_ROOT_PASSWORD\"";
+const MYSQL_RESTORE: &str = "mysql -uroot -p\"$MYSQL_ROOT_PASSWORD\"";
+const VOLUME_IMAGE: &str = "alpine:3";
+
+pub const BACKUP_PRESETS: [&str; 3] = ["postgres", "mysql", "volume"];
+
+/// Archive under `HOME_PATH/backups/<component>`
+#[derive(Debug, Clone, Serialize)]
+pub struct Backup {
+    pub component: String,
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+    #[serde(skip)]
+    pub created: Option<SystemTime>,
+}
+
+enum BackupMethod {
+    /// Dump and restore commands run via exec in a service
+    Exec {
+        service: String,
+        dump: String,
+        restore: String,
+        extension: &'static str,
+    },
+    /// Tarball of a named volume, taken through a throwaway container
+    Volume { volume: String },
+}
+
+pub fn backups_dir(workspace: &Workspace) -> PathBuf {
+    workspace.home_path().join("backups")
+}
+
+/// Backups of one or all components, oldest first
+pub fn list_backups(
+    workspace: &Workspace,
+    component: Option<&str>,
+) -> Result<Vec<Backup>, Box<dyn Error>> {
+    let dir = backups_dir(workspace);
+    let mut backups = Vec::new();
+
+    if !dir.exists() {
+        return Ok(backups);
+    }
+
+    let mut components: Vec<String> = match component {
+        Some(component) => vec![component.to_string()],
+        None => fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect(),
+    };
+    components.sort();
+
+    for component in components {
+        let component_dir = dir.join(&component);
+
+        if !component_dir.is_dir() {
+            continue;
+        }
+
+        let mut component_backups = Vec::new();
+
+        for entry in fs::read_dir(&component_dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+
+            let name = match file_name.strip_suffix(".gz") {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let metadata = entry.metadata()?;
+
+            component_backups.push(Backup {
+                component: component.clone(),
+                name,
+                path: entry.path(),
+                size: metadata.len(),
+                created: metadata.modified().ok(),
+            });
+        }
+
+        // Names start with a timestamp
+        component_backups.sort_by(|a, b| a.name.cmp(&b.name));
+        backups.extend(component_backups);
+    }
+
+    Ok(backups)
+}
+
+/// Backup of a component by full name or a unique part of it, the latest one without a name
+pub fn find_backup(
+    workspace: &Workspace,
+    component: &str,
+    name: Option<&str>,
+) -> Result<Backup, Box<dyn Error>> {
+    let backups = list_backups(workspace, Some(component))?;
+
+    let name = match name {
+        Some(name) => name,
+        None => {
+            return backups
+                .last()
+                .cloned()
+                .ok_or_else(|| format!("Component \"{}\" has no backups", component).into())
+        }
+    };
+
+    if let Some(backup) = backups.iter().find(|backup| backup.name == name) {
+        return Ok(backup.clone());
+    }
+
+    let matching: Vec<&Backup> = backups
+        .iter()
+        .filter(|backup| backup.name.contains(name))
+        .collect();
+
+    match matching.as_slice() {
+        [backup] => Ok((*backup).clone()),
+        [] => Err(Box::new(CliError::new(format!(
+            "No backup \"{}\" of component \"{}\"",
+            name, component
+        )))),
+        _ => Err(Box::new(CliError::new(format!(
+            "Backup \"{}\" is ambiguous, it matches: {}",
+            name,
+            matching
+                .iter()
+                .map(|backup| backup.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )))),
+    }
+}
+
+pub fn create_backup(
+    workspace: &Workspace,
+    component: &Component,
+    name: Option<&str>,
+    options: &GlobalOptions,
+) -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(name) = name {
+        validate_name("backup", name)?;
+    }
+
+    let method = backup_method(component)?;
+
+    let extension = match &method {
+        BackupMethod::Exec { extension, .. } => *extension,
+        BackupMethod::Volume { .. } => "tar",
+    };
+    let stem = match name {
+        Some(name) => format!("{}-{}", timestamp(), name),
+        None => timestamp(),
+    };
+    let dir = backups_dir(workspace).join(&component.name);
+
+    let command = match &method {
+        BackupMethod::Exec { service, dump, .. } => {
+            ensure_running(workspace, component, options)?;
+            exec_command(component, service, dump)
+        }
+        BackupMethod::Volume { volume } => format!(
+            "{} run --rm -v {}:/data {} tar cf - -C /data .",
+            shell_quote(&docker_program()),
+            shell_quote(volume),
+            VOLUME_IMAGE
+        ),
+    };
+
+    if options.dry_run {
+        let path = dir.join(archive_name(&stem, 1, extension));
+        println!(">> {} > {}", command, path.display());
+        return Ok(path);
+    }
+
+    component.write_labels_file()?;
+    let (path, file) = create_archive(&dir, &stem, extension)?;
+
+    if options.debug {
+        println!(">> {} > {}", command, path.display());
+    }
+
+    let mut encoder = GzEncoder::new(file, Compression::default());
+
+    let result = exec_shell_piped(
+        &command,
+        &workspace.cwd,
+        &component.compose_env(),
+        None,
+        Some(&mut encoder),
+    )
+    .and_then(|_| Ok(encoder.finish()?));
+
+    // No half-written archives
+    if let Err(err) = result {
+        fs::remove_file(&path)?;
+        return Err(err);
+    }
+
+    Ok(path)
+}
+
+pub fn restore_backup(
+    workspace: &Workspace,
+    component: &Component,
+    backup: &Backup,
+    options: &GlobalOptions,
+) -> Result<(), Box<dyn Error>> {
+    let method = backup_method(component)?;
+
+    let command = match &method {
+        BackupMethod::Exec {
+            service, restore, ..
+        } => {
+            ensure_running(workspace, component, options)?;
+            exec_command(component, service, restore)
+        }
+        BackupMethod::Volume { volume } => {
+            let runner = component.get_host(workspace)?.unwrap_or(component);
+
+            if WorkspaceStatus::load(workspace, options)?.is_running(&runner.name) {
+                return Err(Box::new(CliError::new(format!(
+                    "Stop \"{}\" before restoring its volume",
+                    component.name
+                ))));
+            }
+
+            format!(
+                "{} run --rm -i -v {}:/data {} sh -c {}",
+                shell_quote(&docker_program()),
+                shell_quote(volume),
+                VOLUME_IMAGE,
+                shell_quote("find /data -mindepth 1 -delete && tar xf - -C /data")
+            )
+        }
+    };
+
+    if options.debug || options.dry_run {
+        println!(">> {} < {}", command, backup.path.display());
+    }
+
+    if options.dry_run {
+        return Ok(());
+    }
+
+    component.write_labels_file()?;
+    let mut decoder = GzDecoder::new(File::open(&backup.path)?);
+
+    exec_shell_piped(
+        &command,
+        &workspace.cwd,
+        &component.compose_env(),
+        Some(&mut decoder),
+        None,
+    )
+}
+
+fn backup_method(component: &Component) -> Result<BackupMethod, Box<dyn Error>> {
+    if let Some(host) = &component.config.hosted_in {
+        return Err(Box::new(CliError::new(format!(
+            "Component \"{}\" is hosted in \"{}\", back up the host instead",
+            component.name, host
+        ))));
+    }
+
+    let config = component
+        .config
+        .backup
+        .clone()
+        .or_else(|| component.template.as_ref()?.backup.clone())
+        .ok_or_else(|| {
+            CliError::new(format!(
+                "Component \"{}\" has no backup section, add one with a preset ({}) or dump and restore commands",
+                component.name,
+                BACKUP_PRESETS.join(", ")
+            ))
+        })?;
+
+    let ctx = component.context.as_ref().unwrap();
+    let service = config
+        .service
+        .as_ref()
+        .map(|service| subst_vars(service, ctx))
+        .unwrap_or_else(|| component.get_service());
+    let dump = config.dump.as_ref().map(|dump| subst_known_vars(dump, ctx));
+    let restore = config
+        .restore
+        .as_ref()
+        .map(|restore| subst_known_vars(restore, ctx));
+
+    let (dump, restore, extension) = match config.preset.as_deref() {
+        Some("postgres") => (
+            dump.unwrap_or_else(|| POSTGRES_DUMP.to_string()),
+            restore.unwrap_or_else(|| POSTGRES_RESTORE.to_string()),
+            "sql",
+        ),
+        Some("mysql") => (
+            dump.unwrap_or_else(|| MYSQL_DUMP.to_string()),
+            restore.unwrap_or_else(|| MYSQL_RESTORE.to_string()),
+            "sql",
+        ),
+        Some("volume") => {
+            let volume = config.volume.as_ref().ok_or_else(|| {
+                format!(
+                    "Component \"{}\" uses the volume backup preset without a volume",
+                    component.name
+                )
+            })?;
+
+            return Ok(BackupMethod::Volume {
+                volume: subst_vars(volume, ctx),
+            });
+        }
+        Some(preset) => {
+            return Err(Box::new(CliError::new(format!(
+                "Unknown backup preset \"{}\", expected one of: {}",
+                preset,
+                BACKUP_PRESETS.join(", ")
+            ))))
+        }
+        None => match (dump, restore) {
+            (Some(dump), Some(restore)) => (dump, restore, "dump"),
+            _ => {
+                return Err(Box::new(CliError::new(format!(
+                    "Backup of \"{}\" needs both dump and restore commands or a preset",
+                    component.name
+                ))))
+            }
+        },
+    };
+
+    Ok(BackupMethod::Exec {
+        service,
+        dump,
+        restore,
+        extension,
+    })
+}
+
+fn ensure_running(
+    workspace: &Workspace,
+    component: &Component,
+    options: &GlobalOptions,
+) -> Result<(), Box<dyn Error>> {
+    if options.dry_run {
+        return Ok(());
+    }
+
+    let runner = component.get_host(workspace)?.unwrap_or(component);
+
+    if WorkspaceStatus::load(workspace, options)?.is_running(&runner.name) {
+        return Ok(());
+    }
+
+    Err(Box::new(CliError::new(format!(
+        "Component \"{}\" is not running, start it first",
+        component.name
+    ))))
+}
+
+fn exec_command(component: &Component, service: &str, command: &str) -> String {
+    let args = [
+        "exec".to_string(),
+        "-T".to_string(),
+        service.to_string(),
+        "sh".to_string(),
+        "-c".to_string(),
+        shell_quote(command),
+    ];
+
+    component.compose_command(&args).join(" ")
+}
+
+fn archive_name(stem: &str, attempt: usize, extension: &str) -> String {
+    match attempt {
+        1 => format!("{}.{}.gz", stem, extension),
+        _ => format!("{}_{}.{}.gz", stem, attempt, extension),
+    }
+}
+
+/// Creates a new archive file, one taken in the same second as an existing
+/// archive gets a numbered suffix instead of overwriting it
+fn create_archive(
+    dir: &Path,
+    stem: &str,
+    extension: &str,
+) -> Result<(PathBuf, File), Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+
+    for attempt in 1.. {
+        let path = dir.join(archive_name(stem, attempt, extension));
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(Box::new(err)),
+        }
+    }
+
+    unreachable!()
+}
+
+fn timestamp() -> String {
+    let now = OffsetDateTime::now_utc();
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        args::{parse_global_options, AppArgs},
+        core::SystemPath,
+        workspace::create_workspace,
+    };
+    use clap::Parser;
+
+    fn workspace(root: &Path) -> Workspace {
+        let config = r#"{
+            "name": "shop",
+            "version": "1.0.0",
+            "variables": {},
+            "components": {
+                "postgres": { "path": "/srv/postgres" },
+                "database": {
+                    "path": "/srv/database",
+                    "extends": "postgres",
+                    "backup": { "preset": "postgres" }
+                },
+                "mysql": {
+                    "path": "/srv/mysql",
+                    "backup": { "preset": "mysql", "service": "db", "dump": "mysqldump -u${DB_USER} app" },
+                    "variables": { "DB_USER": "shop" }
+                },
+                "uploads": {
+                    "path": "/srv/uploads",
+                    "backup": { "preset": "volume", "volume": "${COMPOSE_PROJECT_NAME}_files" }
+                },
+                "custom": {
+                    "path": "/srv/custom",
+                    "backup": { "dump": "dump ${PGUSER:-app}" }
+                },
+                "broken": { "path": "/srv/broken", "backup": { "preset": "redis" } },
+                "nginx": { "path": "/srv/nginx" },
+                "admin": { "path": "/srv/admin", "hosted_in": "nginx", "backup": { "preset": "mysql" } }
+            }
+        }"#;
+        let cli = AppArgs::try_parse_from(["uniform", "ps"]).unwrap();
+        let root = root.display().to_string();
+
+        let mut ws = create_workspace(SystemPath::new(&root), &root);
+        ws.config = Some(serde_json::from_str(config).unwrap());
+        ws.init(&parse_global_options(&cli)).unwrap();
+
+        ws
+    }
+
+    fn method(ws: &Workspace, name: &str) -> Result<BackupMethod, Box<dyn Error>> {
+        backup_method(&ws.components[name])
+    }
+
+    #[test]
+    fn resolves_backup_presets() {
+        let ws = workspace(Path::new("/srv"));
+
+        match method(&ws, "database").unwrap() {
+            BackupMethod::Exec {
+                service,
+                dump,
+                restore,
+                extension,
+            } => {
+                assert_eq!(service, "app");
+                assert_eq!(dump, POSTGRES_DUMP);
+                assert_eq!(restore, POSTGRES_RESTORE);
+                assert_eq!(extension, "sql");
+            }
+            BackupMethod::Volume { .. } => panic!("expected an exec backup"),
+        }
+
+        // Commands given next to a preset replace the preset ones
+        match method(&ws, "mysql").unwrap() {
+            BackupMethod::Exec {
+                service,
+                dump,
+                restore,
+                ..
+            } => {
+                assert_eq!(service, "db");
+                assert_eq!(dump, "mysqldump -ushop app");
+                assert_eq!(restore, MYSQL_RESTORE);
+            }
+            BackupMethod::Volume { .. } => panic!("expected an exec backup"),
+        }
+
+        match method(&ws, "uploads").unwrap() {
+            BackupMethod::Volume { volume } => assert_eq!(volume, "shop-uploads_files"),
+            BackupMethod::Exec { .. } => panic!("expected a volume backup"),
+        }
+    }
+
+    #[test]
+    fn rejects_incomplete_backup_config() {
+        let ws = workspace(Path::new("/srv"));
+
+        let err = method(&ws, "custom").err().unwrap().to_string();
+        assert!(err.contains("needs both dump and restore commands"));
+
+        let err = method(&ws, "broken").err().unwrap().to_string();
+        assert!(err.contains("Unknown backup preset \"redis\""));
+
+        let err = method(&ws, "nginx").err().unwrap().to_string();
+        assert!(err.contains("has no backup section"));
+
+        let err = method(&ws, "admin").err().unwrap().to_string();
+        assert!(err.contains("back up the host instead"));
+    }
+
+    #[test]
+    fn finds_backups_by_name() {
+        let root = std::env::temp_dir().join(format!("uniform-backup-{}", std::process::id()));
+        let ws = workspace(&root);
+        let dir = backups_dir(&ws).join("database");
+        fs::create_dir_all(&dir).unwrap();
+
+        for file in [
+            "20260101-100000-before-migration.sql.gz",
+            "20260101-100000-before-migration.sql_old.gz",
+            "20260102-100000.sql.gz",
+            "20260103-100000-nightly.sql.gz",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+
+        let find = |name| {
+            find_backup(&ws, "database", name)
+                .map(|backup| backup.name)
+                .map_err(|err| err.to_string())
+        };
+
+        let latest = find(None);
+        let exact = find(Some("20260101-100000-before-migration.sql"));
+        let unique = find(Some("nightly"));
+        let ambiguous = find(Some("before-migration"));
+        let missing = find(Some("weekly"));
+        let none = find_backup(&ws, "uploads", None).map(|backup| backup.name);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(latest.unwrap(), "20260103-100000-nightly.sql");
+        assert_eq!(exact.unwrap(), "20260101-100000-before-migration.sql");
+        assert_eq!(unique.unwrap(), "20260103-100000-nightly.sql");
+        assert!(ambiguous
+            .unwrap_err()
+            .contains("is ambiguous, it matches: "));
+        assert!(missing.unwrap_err().contains("No backup \"weekly\""));
+        assert!(none.is_err());
+    }
+
+    #[test]
+    fn same_second_archives_get_a_suffix() {
+        let dir = std::env::temp_dir().join(format!("uniform-archive-{}", std::process::id()));
+
+        let (first, _) = create_archive(&dir, "20260101-100000", "sql").unwrap();
+        let (second, _) = create_archive(&dir, "20260101-100000", "sql").unwrap();
+        let (third, _) = create_archive(&dir, "20260101-100000", "sql").unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(first, dir.join("20260101-100000.sql.gz"));
+        assert_eq!(second, dir.join("20260101-100000_2.sql.gz"));
+        assert_eq!(third, dir.join("20260101-100000_3.sql.gz"));
+        assert!(first.file_name() < second.file_name());
+    }
+}
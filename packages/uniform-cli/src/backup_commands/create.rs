@@ -0,0 +1,42 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::ExecuteTrait, backup::create_backup, core::GlobalOptions, settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct BackupCreateCommand {
+    /// Component to back up
+    component: String,
+
+    /// Label appended to the timestamp of the archive
+    #[arg(long)]
+    name: Option<String>,
+}
+
+impl ExecuteTrait for BackupCreateCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let name = ws.resolve_name(&self.component)?;
+        let component = ws.components.get(&name).unwrap();
+
+        let path = create_backup(&ws, component, self.name.as_deref(), global_options)?;
+
+        if !global_options.dry_run {
+            println!("Backed up {} to {}", name.bright_green(), path.display());
+        }
+
+        Ok(None)
+    }
+}
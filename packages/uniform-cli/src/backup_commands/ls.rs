@@ -0,0 +1,88 @@
+use clap::Args;
+use std::error::Error;
+use std::time::SystemTime;
+
+use prettytable::{format, Table};
+use time::OffsetDateTime;
+
+use crate::{
+    args::ExecuteTrait, backup::list_backups, core::GlobalOptions, settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct BackupLsCommand {
+    /// Only list backups of this component
+    component: Option<String>,
+}
+
+impl ExecuteTrait for BackupLsCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let component = match &self.component {
+            Some(component) => Some(ws.resolve_name(component)?),
+            None => None,
+        };
+        let backups = list_backups(&ws, component.as_deref())?;
+
+        if global_options.json {
+            println!("{}", serde_json::to_string_pretty(&backups)?);
+            return Ok(None);
+        }
+
+        let mut table = Table::new();
+        table.set_titles(row!["Component", "Name", "Size", "Created"]);
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        for backup in &backups {
+            table.add_row(row![
+                backup.component,
+                backup.name,
+                format_size(backup.size),
+                backup.created.map(format_time).unwrap_or_default()
+            ]);
+        }
+
+        table.printstd();
+
+        Ok(None)
+    }
+}
+
+fn format_size(size: u64) -> String {
+    let units = ["B", "KB", "MB", "GB"];
+    let mut value = size as f64;
+    let mut unit = 0;
+
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", size, units[0])
+    } else {
+        format!("{:.1} {}", value, units[unit])
+    }
+}
+
+fn format_time(time: SystemTime) -> String {
+    let time = OffsetDateTime::from(time);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        time.year(),
+        time.month() as u8,
+        time.day(),
+        time.hour(),
+        time.minute()
+    )
+}
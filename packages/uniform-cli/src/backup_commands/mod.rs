@@ -0,0 +1,4 @@
+pub mod create;
+pub mod ls;
+pub mod restore;
+pub mod rm;
@@ -0,0 +1,56 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    backup::{find_backup, restore_backup},
+    core::{confirm, GlobalOptions},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct BackupRestoreCommand {
+    /// Component to restore
+    component: String,
+
+    /// Backup to restore, the latest one by default
+    backup: Option<String>,
+}
+
+impl ExecuteTrait for BackupRestoreCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let name = ws.resolve_name(&self.component)?;
+        let component = ws.components.get(&name).unwrap();
+        let backup = find_backup(&ws, &name, self.backup.as_deref())?;
+
+        let question = format!(
+            "Restore {} from {}? Its current data will be replaced",
+            name, backup.name
+        );
+
+        if !global_options.dry_run && !confirm(&question, global_options)? {
+            return Err(Box::new(CliError::new(
+                "Restore cancelled, pass --force to restore without asking",
+            )));
+        }
+
+        restore_backup(&ws, component, &backup, global_options)?;
+
+        if !global_options.dry_run {
+            println!("Restored {} from {}", name.bright_green(), backup.name);
+        }
+
+        Ok(None)
+    }
+}
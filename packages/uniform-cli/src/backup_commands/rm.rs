@@ -0,0 +1,51 @@
+use clap::Args;
+use std::error::Error;
+
+use crate::{
+    args::ExecuteTrait, backup::find_backup, core::GlobalOptions, settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct BackupRmCommand {
+    /// Component the backups belong to
+    component: String,
+
+    /// Backups to remove
+    #[clap(required = true)]
+    backups: Vec<String>,
+}
+
+impl ExecuteTrait for BackupRmCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let name = ws.resolve_name(&self.component)?;
+
+        // Resolve all of them first so a typo removes nothing
+        let backups = self
+            .backups
+            .iter()
+            .map(|backup| find_backup(&ws, &name, Some(backup)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for backup in backups {
+            if global_options.dry_run {
+                println!("Would remove backup {}", backup.name);
+                continue;
+            }
+
+            std::fs::remove_file(&backup.path)?;
+            println!("Removed backup {}", backup.name);
+        }
+
+        Ok(None)
+    }
+}
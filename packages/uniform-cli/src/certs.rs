@@ -0,0 +1,259 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use rcgen::{
+    BasicConstraints, CertificateParams, DistinguishedName, DnType, ExtendedKeyUsagePurpose, IsCa,
+    KeyPair, KeyUsagePurpose,
+};
+use time::{Duration, OffsetDateTime};
+
+use crate::{args::CliError, proxy::collect_routes, workspace::Workspace};
+
+const CA_DAYS: i64 = 3650;
+// macOS rejects server certificates valid for longer than 825 days
+const CERT_DAYS: i64 = 825;
+
+/// Workspace-local certificate authority and the certificate it issues
+pub struct CertPaths {
+    pub ca_cert: PathBuf,
+    pub ca_key: PathBuf,
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+impl CertPaths {
+    pub fn new(workspace: &Workspace) -> CertPaths {
+        let ctx = &workspace.context;
+        let dir = workspace.home_path().join("certs");
+
+        CertPaths {
+            ca_cert: PathBuf::from(ctx.get("TLS_CA").unwrap()),
+            ca_key: dir.join("ca-key.pem"),
+            cert: PathBuf::from(ctx.get("TLS_CERT").unwrap()),
+            key: PathBuf::from(ctx.get("TLS_KEY").unwrap()),
+        }
+    }
+}
+
+/// Creates the CA, an existing one is kept unless `force` is set since
+/// replacing it invalidates every trust store it was added to
+pub fn init_ca(workspace: &Workspace, force: bool) -> Result<bool, Box<dyn Error>> {
+    let paths = CertPaths::new(workspace);
+
+    if paths.ca_cert.exists() && !force {
+        return Ok(false);
+    }
+
+    let name = &workspace.config.as_ref().unwrap().name;
+    let now = OffsetDateTime::now_utc();
+
+    let mut params = CertificateParams::default();
+    params.distinguished_name = DistinguishedName::new();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, format!("uniform {} local CA", name));
+    params
+        .distinguished_name
+        .push(DnType::OrganizationName, "uniform development CA");
+    params.is_ca = IsCa::Ca(BasicConstraints::Constrained(0));
+    params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+    params.not_before = now - Duration::days(1);
+    params.not_after = now + Duration::days(CA_DAYS);
+
+    let key = KeyPair::generate()?;
+    let cert = params.self_signed(&key)?;
+
+    write_file(&paths.ca_cert, &cert.pem(), false)?;
+    write_file(&paths.ca_key, &key.serialize_pem(), true)?;
+
+    Ok(true)
+}
+
+/// Signs a certificate for `*.BASE_DOMAIN` and every component hostname,
+/// returns the names it covers
+pub fn issue(workspace: &Workspace) -> Result<Vec<String>, Box<dyn Error>> {
+    let paths = CertPaths::new(workspace);
+
+    if !paths.ca_cert.exists() || !paths.ca_key.exists() {
+        return Err(Box::new(CliError::new(
+            "No certificate authority yet, run `uniform certs init` first",
+        )));
+    }
+
+    let ca_key = KeyPair::from_pem(&fs::read_to_string(&paths.ca_key)?)?;
+    let ca_cert_pem = fs::read_to_string(&paths.ca_cert)?;
+    let ca_cert = CertificateParams::from_ca_cert_pem(&ca_cert_pem)?.self_signed(&ca_key)?;
+
+    let names = collect_names(workspace)?;
+    let now = OffsetDateTime::now_utc();
+
+    let mut params = CertificateParams::new(names.clone())?;
+    params.distinguished_name = DistinguishedName::new();
+    params
+        .distinguished_name
+        .push(DnType::CommonName, names[0].clone());
+    params.key_usages = vec![
+        KeyUsagePurpose::DigitalSignature,
+        KeyUsagePurpose::KeyEncipherment,
+    ];
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+    params.not_before = now - Duration::days(1);
+    params.not_after = now + Duration::days(CERT_DAYS);
+
+    let key = KeyPair::generate()?;
+    let cert = params.signed_by(&key, &ca_cert, &ca_key)?;
+
+    // Full chain, so servers can present the CA along with the certificate
+    write_file(
+        &paths.cert,
+        &format!("{}{}", cert.pem(), ca_cert_pem),
+        false,
+    )?;
+    write_file(&paths.key, &key.serialize_pem(), true)?;
+
+    Ok(names)
+}
+
+fn collect_names(workspace: &Workspace) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut names = Vec::new();
+
+    if let Some(base_domain) = workspace.context.get("BASE_DOMAIN") {
+        names.push(format!("*.{}", base_domain));
+        names.push(base_domain.clone());
+    }
+
+    for route in collect_routes(workspace)? {
+        let covered = names.iter().any(|name| covers(name, &route.hostname));
+
+        if !covered {
+            names.push(route.hostname);
+        }
+    }
+
+    if names.is_empty() {
+        return Err(Box::new(CliError::new(
+            "Nothing to issue a certificate for, set BASE_DOMAIN or component hostnames",
+        )));
+    }
+
+    Ok(names)
+}
+
+// A wildcard only matches a single label
+fn covers(name: &str, hostname: &str) -> bool {
+    match name.strip_prefix("*.") {
+        Some(domain) => hostname
+            .split_once('.')
+            .map(|(_, rest)| rest == domain)
+            .unwrap_or(false),
+        None => name == hostname,
+    }
+}
+
+fn write_file(path: &Path, content: &str, private: bool) -> Result<(), Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    // Created with the final mode, so a key is never readable by others
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options.open(path)?.write_all(content.as_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        args::{parse_global_options, AppArgs},
+        core::SystemPath,
+        workspace::create_workspace,
+    };
+    use clap::Parser;
+
+    fn workspace(config: &str) -> Workspace {
+        let cli = AppArgs::try_parse_from(["uniform", "ps"]).unwrap();
+
+        let mut ws = create_workspace(SystemPath::new("/srv"), "/srv");
+        ws.config = Some(serde_json::from_str(config).unwrap());
+        ws.init(&parse_global_options(&cli)).unwrap();
+
+        ws
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn keys_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("uniform-certs-{}", std::process::id()));
+        let key = dir.join("key.pem");
+        write_file(&key, "key", true).unwrap();
+        let mode = fs::metadata(&key).unwrap().permissions().mode();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn wildcard_covers_a_single_label() {
+        assert!(covers("*.shop.test", "api.shop.test"));
+        assert!(!covers("*.shop.test", "admin.api.shop.test"));
+        assert!(!covers("*.shop.test", "shop.test"));
+        assert!(!covers("*.shop.test", "api.blog.test"));
+        assert!(covers("shop.test", "shop.test"));
+        assert!(!covers("shop.test", "api.shop.test"));
+    }
+
+    #[test]
+    fn names_cover_base_domain_and_uncovered_hostnames() {
+        let ws = workspace(
+            r#"{
+                "name": "shop",
+                "version": "1.0.0",
+                "variables": { "BASE_DOMAIN": "shop.test" },
+                "components": {
+                    "backend": { "path": "/srv/backend", "hostname": "api.${BASE_DOMAIN}" },
+                    "admin": { "path": "/srv/admin", "hostname": "admin.api.${BASE_DOMAIN}" },
+                    "docs": { "path": "/srv/docs", "hostname": "https://docs.example.com" },
+                    "database": { "path": "/srv/database" }
+                }
+            }"#,
+        );
+
+        assert_eq!(
+            collect_names(&ws).unwrap(),
+            [
+                "*.shop.test",
+                "shop.test",
+                "admin.api.shop.test",
+                "docs.example.com"
+            ]
+        );
+    }
+
+    #[test]
+    fn names_need_a_base_domain_or_hostnames() {
+        let ws = workspace(
+            r#"{
+                "name": "shop",
+                "version": "1.0.0",
+                "variables": {},
+                "components": { "database": { "path": "/srv/database" } }
+            }"#,
+        );
+
+        assert!(collect_names(&ws).is_err());
+    }
+}
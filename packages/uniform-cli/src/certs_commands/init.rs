@@ -0,0 +1,49 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::ExecuteTrait,
+    certs::{init_ca, CertPaths},
+    core::GlobalOptions,
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct InitCommand {
+    /// Replace an existing certificate authority
+    #[arg(long)]
+    force: bool,
+}
+
+impl ExecuteTrait for InitCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let paths = CertPaths::new(&ws);
+
+        if !init_ca(&ws, self.force || global_options.force)? {
+            println!(
+                "Certificate authority already exists at {}, use --force to replace it",
+                paths.ca_cert.display().to_string().bright_yellow()
+            );
+            return Ok(None);
+        }
+
+        println!(
+            "Created certificate authority {}",
+            paths.ca_cert.display().to_string().bright_green()
+        );
+        println!("Add it to your system and browser trust stores, then run `uniform certs issue`");
+
+        Ok(None)
+    }
+}
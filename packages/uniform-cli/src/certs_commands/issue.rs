@@ -0,0 +1,39 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::ExecuteTrait,
+    certs::{issue, CertPaths},
+    core::GlobalOptions,
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct IssueCommand {}
+
+impl ExecuteTrait for IssueCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let names = issue(&ws)?;
+        let paths = CertPaths::new(&ws);
+
+        println!("Issued certificate for:");
+        for name in names {
+            println!("  {}", name.bright_green());
+        }
+        println!("TLS_CERT={}", paths.cert.display());
+        println!("TLS_KEY={}", paths.key.display());
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,271 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::GlobalOptions,
+    resources::docker,
+    settings::Settings,
+    status::WorkspaceStatus,
+    workspace::{create_workspace, Workspace},
+};
+
+/// Copies files between the host and a component container
+#[derive(Debug, Args)]
+pub struct CpCommand {
+    /// Host path, or component[:service]:path in a container
+    source: String,
+
+    /// Host path, or component[:service]:path in a container
+    destination: String,
+}
+
+enum Location {
+    Host(String),
+    Container {
+        component: String,
+        service: Option<String>,
+        path: String,
+    },
+}
+
+impl ExecuteTrait for CpCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let source = parse_location(&ws, &self.source)?;
+        let destination = parse_location(&ws, &self.destination)?;
+
+        match (source, destination) {
+            (
+                Location::Container {
+                    component,
+                    service,
+                    path,
+                },
+                Location::Host(host_path),
+            ) => {
+                let (container, path) =
+                    resolve_container(&ws, &component, service, &path, global_options)?;
+                let from = format!("{}:{}", container, path);
+
+                if global_options.debug || global_options.dry_run {
+                    println!(">> docker cp {} {}", from, host_path);
+                }
+
+                if global_options.dry_run {
+                    return Ok(None);
+                }
+
+                docker(&["cp", &from, &host_path])?;
+                println!(
+                    "Copied {}:{} to {}",
+                    component.bright_green(),
+                    path,
+                    host_path
+                );
+            }
+            (
+                Location::Host(host_path),
+                Location::Container {
+                    component,
+                    service,
+                    path,
+                },
+            ) => {
+                let (container, path) =
+                    resolve_container(&ws, &component, service, &path, global_options)?;
+                let to = format!("{}:{}", container, path);
+
+                let into_dir = docker(&["exec", &container, "test", "-d", &path]).is_ok();
+                let copied = copied_paths(&host_path, &path, into_dir)?;
+                let owner = ws
+                    .components
+                    .get(&component)
+                    .unwrap()
+                    .get_user(global_options)?;
+
+                // Copied files keep the host uid otherwise
+                let mut chown = vec!["exec", "-u", "0", &container, "chown", "-R", &owner];
+                chown.extend(copied.iter().map(|path| path.as_str()));
+
+                if global_options.dry_run {
+                    println!(">> docker cp {} {}", host_path, to);
+                    if !copied.is_empty() {
+                        println!(">> docker {}", chown.join(" "));
+                    }
+                    return Ok(None);
+                }
+
+                docker(&["cp", &host_path, &to])?;
+                if !copied.is_empty() {
+                    docker(&chown)?;
+                }
+
+                println!(
+                    "Copied {} to {}:{}",
+                    host_path,
+                    component.bright_green(),
+                    path
+                );
+            }
+            (Location::Host(_), Location::Host(_)) => {
+                return Err(Box::new(CliError::new(
+                    "One of the paths must be in a container: component[:service]:path",
+                )))
+            }
+            (Location::Container { .. }, Location::Container { .. }) => {
+                return Err(Box::new(CliError::new(
+                    "Copying between containers is not supported, copy to the host first",
+                )))
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// `component[:service]:path` when the part before the first colon is a
+/// component or alias, a host path otherwise
+fn parse_location(workspace: &Workspace, arg: &str) -> Result<Location, Box<dyn Error>> {
+    let (name, rest) = match arg.split_once(':') {
+        Some(parts) => parts,
+        None => return Ok(Location::Host(arg.to_string())),
+    };
+
+    let is_component =
+        workspace.components.contains_key(name) || workspace.aliases.contains_key(name);
+
+    if !is_component {
+        return Ok(Location::Host(arg.to_string()));
+    }
+
+    let component = workspace.resolve_name(name)?;
+
+    let (service, path) = match rest.split_once(':') {
+        Some((service, path)) if !service.is_empty() && !service.contains('/') => {
+            (Some(service.to_string()), path.to_string())
+        }
+        _ => (None, rest.to_string()),
+    };
+
+    if path.is_empty() {
+        return Err(Box::new(CliError::new(format!(
+            "Missing container path in \"{}\"",
+            arg
+        ))));
+    }
+
+    Ok(Location::Container {
+        component,
+        service,
+        path,
+    })
+}
+
+/// Running container of the component, its host for hosted components, and
+/// the path in it with relative paths based on `exec_path`
+fn resolve_container(
+    workspace: &Workspace,
+    name: &str,
+    service: Option<String>,
+    path: &str,
+    options: &GlobalOptions,
+) -> Result<(String, String), Box<dyn Error>> {
+    let component = workspace.components.get(name).unwrap();
+    let runner = component.get_host(workspace)?.unwrap_or(component);
+
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        let svc_path = component.context.as_ref().unwrap().get("SVC_PATH").cloned();
+        let exec_dir = svc_path
+            .and_then(|svc_path| component.get_exec_dir(&svc_path))
+            .ok_or_else(|| {
+                CliError::new(format!(
+                    "Component \"{}\" has no exec_path, use an absolute container path",
+                    name
+                ))
+            })?;
+
+        format!("{}/{}", exec_dir.trim_end_matches('/'), path)
+    };
+
+    let service = service.unwrap_or_else(|| runner.get_service());
+    let status = WorkspaceStatus::load(workspace, options)?;
+
+    match status.running_container(&runner.name, &service) {
+        Some(container) => Ok((container.id.clone(), path)),
+        None => Err(Box::new(CliError::new(format!(
+            "Service \"{}\" of \"{}\" is not running, start it first",
+            service, runner.name
+        )))),
+    }
+}
+
+/// Container paths `docker cp` writes when copying the host path to `path`.
+/// Into an existing directory it puts the source under its own name, or
+/// only its entries for a path ending in `.`.
+fn copied_paths(
+    host_path: &str,
+    path: &str,
+    into_dir: bool,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    if !into_dir {
+        return Ok(vec![path.to_string()]);
+    }
+
+    let dir = path.trim_end_matches('/');
+    let host = Path::new(host_path);
+
+    // Path drops a trailing `.`, so "src/." would look like "src"
+    let names = if host_path == "." || host_path.ends_with("/.") {
+        fs::read_dir(host)?
+            .map(|entry| Ok(entry?.file_name()))
+            .collect::<Result<Vec<_>, std::io::Error>>()?
+    } else {
+        let name = host.canonicalize()?.file_name().map(|name| name.to_owned());
+        name.into_iter().collect()
+    };
+
+    Ok(names
+        .iter()
+        .map(|name| format!("{}/{}", dir, name.to_string_lossy()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copied_paths_follow_docker_cp() {
+        let root = std::env::temp_dir().join(format!("uniform-cp-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/nested")).unwrap();
+        fs::write(root.join("src/file.txt"), "").unwrap();
+        let src = root.join("src").display().to_string();
+
+        assert_eq!(copied_paths(&src, "/app/new", false).unwrap(), ["/app/new"]);
+        assert_eq!(copied_paths(&src, "/app/", true).unwrap(), ["/app/src"]);
+        assert_eq!(
+            copied_paths(&format!("{}/nested/..", src), "/app", true).unwrap(),
+            ["/app/src"]
+        );
+
+        let mut entries = copied_paths(&format!("{}/.", src), "/app", true).unwrap();
+        entries.sort();
+        assert_eq!(entries, ["/app/file.txt", "/app/nested"]);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+use clap::Args;
+use colored::Colorize;
+use std::{
+    error::Error,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+use tokio::runtime::Runtime;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::GlobalOptions,
+    dns::DnsServer,
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+/// Runs a DNS server resolving every name under BASE_DOMAIN to a local address
+#[derive(Debug, Args)]
+pub struct DnsCommand {
+    /// Address to listen on, 5353 is left to mDNS
+    #[arg(short, long, default_value = "127.0.0.1:5300")]
+    listen: String,
+
+    /// IPv4 address A queries are answered with
+    #[arg(long, default_value = "127.0.0.1")]
+    address: Ipv4Addr,
+
+    /// IPv6 address AAAA queries are answered with, none by default
+    #[arg(long)]
+    address6: Option<Ipv6Addr>,
+
+    /// Server other names are forwarded to (e.g. 1.1.1.1:53), refused otherwise
+    #[arg(long)]
+    forward: Option<SocketAddr>,
+}
+
+impl ExecuteTrait for DnsCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let domain = ws.context.get("BASE_DOMAIN").cloned().ok_or_else(|| {
+            CliError::new("BASE_DOMAIN is not set, there is no domain to answer for")
+        })?;
+
+        println!(
+            "Answering *.{} with {} on {}",
+            domain.bright_green(),
+            self.address,
+            self.listen.bright_blue()
+        );
+        println!("To route the domain here with systemd-resolved:");
+        println!("  resolvectl dns lo {}", self.listen);
+        println!("  resolvectl domain lo ~{}", domain);
+
+        let server = Arc::new(DnsServer {
+            domain,
+            address: self.address,
+            address6: self.address6,
+            forward: self.forward,
+        });
+
+        let rt = Runtime::new()?;
+        rt.block_on(server.run(&self.listen))
+            .map_err(|err| err as Box<dyn Error>)?;
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,103 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::{shell_quote, subst_known_vars, GlobalOptions},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct DoCommand {
+    /// Component the command belongs to
+    component: String,
+
+    /// Custom command of the component
+    command: String,
+
+    /// Appended to the command line
+    #[clap(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+
+    /// Invoked as `uniform <component> <command>`
+    #[clap(skip)]
+    external: bool,
+}
+
+impl DoCommand {
+    /// Built from `uniform <component> <command> [args]`
+    pub fn external(args: &[String]) -> Result<DoCommand, Box<dyn Error>> {
+        match args {
+            [component, command, rest @ ..] => Ok(DoCommand {
+                component: component.clone(),
+                command: command.clone(),
+                args: rest.to_vec(),
+                external: true,
+            }),
+            [component] => Err(Box::new(CliError::new(format!(
+                "Missing command, see `uniform help {}`",
+                component
+            )))),
+            [] => Err(Box::new(CliError::new("Missing component"))),
+        }
+    }
+}
+
+impl ExecuteTrait for DoCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let name = match ws.resolve_name(&self.component) {
+            Err(err) if self.external => {
+                return Err(Box::new(CliError::new(format!(
+                    "\"{}\" is neither a uniform command nor a component. {}",
+                    self.component, err
+                ))))
+            }
+            result => result?,
+        };
+        let mut component = ws.components.get(&name).unwrap().clone();
+
+        if component.config.is_template.unwrap_or(false) {
+            return Err(Box::new(CliError::new(format!(
+                "You can't run commands of a service template, please use instances: {}",
+                ws.get_executable_component_names().join(",")
+            ))));
+        }
+
+        let commands = component.get_commands();
+        let custom = commands.get(&self.command).ok_or_else(|| {
+            CliError::new(format!(
+                "Component \"{}\" has no command \"{}\", available: {}",
+                name,
+                self.command,
+                commands.keys().cloned().collect::<Vec<_>>().join(", ")
+            ))
+        })?;
+
+        let mut line = subst_known_vars(custom.command(), component.context.as_ref().unwrap());
+        for arg in &self.args {
+            line.push(' ');
+            line.push_str(&shell_quote(arg));
+        }
+
+        println!("{} {} $ {}", name.bright_green(), self.command, line);
+
+        // Through a shell so commands can chain and pipe
+        let mut options = global_options.clone();
+        options.cmd = Some(vec!["sh".to_string(), "-c".to_string(), shell_quote(&line)]);
+
+        component.exec_custom_command(&mut ws, &options)?;
+
+        Ok(None)
+    }
+}
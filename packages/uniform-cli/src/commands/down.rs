@@ -0,0 +1,91 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::GlobalOptions,
+    resources::{list_volumes, remove_volume, volume_component},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+/// Removes containers of components, all of them when none are given
+#[derive(Debug, Args)]
+pub struct DownCommand {
+    services: Vec<String>,
+
+    /// Remove the named volumes of the components too
+    #[arg(short, long)]
+    volumes: bool,
+}
+
+impl ExecuteTrait for DownCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let names = if self.services.is_empty() {
+            ws.components
+                .values()
+                .filter(|c| !c.config.is_template.unwrap_or(false))
+                .filter(|c| c.config.hosted_in.is_none())
+                .map(|c| c.name.clone())
+                .collect()
+        } else {
+            self.services
+                .iter()
+                .map(|name| ws.resolve_name(name))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        for name in &names {
+            let component = ws.components.get(name).unwrap();
+
+            if component.config.is_template.unwrap_or(false) {
+                return Err(Box::new(CliError::new(format!(
+                    "You can't take down a service template, please use instances: {}",
+                    ws.get_executable_component_names().join(",")
+                ))));
+            }
+
+            println!("Taking down {}...", name.green());
+
+            match component.down(&ws, self.volumes, global_options) {
+                Err(err) => println!("{} {}", "Failed to take down component:".bright_red(), err),
+                Ok(Some(out)) => println!("{}", out.green()),
+                Ok(None) => {}
+            }
+        }
+
+        if !self.volumes {
+            return Ok(None);
+        }
+
+        // Compose removed the volumes it created, workspace volumes are left
+        for volume in list_volumes(&ws)? {
+            let owner = match volume_component(&ws, &volume) {
+                Some(owner) if names.contains(&owner) => owner,
+                _ => continue,
+            };
+
+            if global_options.dry_run {
+                println!("Would remove volume {} of {}", volume.name, owner);
+                continue;
+            }
+
+            match remove_volume(&volume.name) {
+                Ok(()) => println!("Removed volume {} of {}", volume.name.bright_green(), owner),
+                Err(err) => println!("{} {}", "Failed to remove volume:".bright_red(), err),
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,99 @@
+use clap::{builder::PossibleValuesParser, Args};
+use indexmap::IndexMap;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::{shell_quote, GlobalOptions},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+pub const ENV_FORMATS: [&str; 3] = ["dotenv", "shell", "json"];
+
+/// Prints the workspace or component context, e.g. for `eval "$(uniform env --format shell)"`
+#[derive(Debug, Args)]
+pub struct EnvCommand {
+    /// Component to print the context of, the workspace one if not set
+    component: Option<String>,
+
+    #[arg(long, default_value = "dotenv", value_parser = PossibleValuesParser::new(ENV_FORMATS))]
+    format: String,
+}
+
+impl ExecuteTrait for EnvCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let context = ws.get_context(self.component.as_deref())?;
+        let format = if global_options.json {
+            "json"
+        } else {
+            self.format.as_str()
+        };
+
+        print!("{}", render_env(format, &context)?);
+
+        Ok(None)
+    }
+}
+
+pub fn render_env(
+    format: &str,
+    context: &IndexMap<String, String>,
+) -> Result<String, Box<dyn Error>> {
+    let mut result = String::new();
+
+    match format {
+        "dotenv" => {
+            for (k, v) in context {
+                result.push_str(&format!("{}={}\n", k, dotenv_quote(v)));
+            }
+        }
+        "shell" => {
+            for (k, v) in context {
+                result.push_str(&format!("export {}={}\n", k, shell_quote(v)));
+            }
+        }
+        "json" => {
+            result = serde_json::to_string_pretty(context)?;
+            result.push('\n');
+        }
+        other => {
+            return Err(Box::new(CliError::new(format!(
+                "Unknown env format \"{}\", expected one of: {}",
+                other,
+                ENV_FORMATS.join(", ")
+            ))))
+        }
+    }
+
+    Ok(result)
+}
+
+// Double quotes only when needed, the way dotenv parsers expect them
+fn dotenv_quote(value: &str) -> String {
+    let plain = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-/.:,@%+".contains(c));
+
+    if plain {
+        return value.to_string();
+    }
+
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+            .replace('$', "\\$")
+    )
+}
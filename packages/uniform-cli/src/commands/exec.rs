@@ -13,7 +13,17 @@ pub struct ExecCommand {
     #[clap(required = true)]
     service: String,
 
-    #[clap(value_delimiter = ' ', num_args = 1.., required = true)]
+    /// Working dir inside the container, defaults to the component's exec_path
+    #[arg(short, long)]
+    workdir: Option<String>,
+
+    #[clap(
+        value_delimiter = ' ',
+        num_args = 1..,
+        required = true,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
     command: Vec<String>,
 }
 
@@ -32,48 +42,33 @@ impl ExecuteTrait for ExecCommand {
             return Err(ws_load.unwrap_err());
         }
 
-        ws.init(&global_options);
-
-        let comp = ws.components.get(&self.service);
-
-        match comp {
-            None => {
-                return Err(Box::new(CliError::new(format!(
-                    "Unknown service '{:?}'. Possible services are: {:?}",
-                    &self.service,
-                    ws.get_executable_component_names()
-                ))));
-            }
-            Some(component) => {
-                if component.config.is_template.unwrap_or(false) {
-                    return Err(Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!(
-                            "You can't execute on a service template, please use instances: {}",
-                            ws.get_executable_component_names().join(",")
-                        ),
-                    )));
-                }
-
-                println!(
-                    "Found component {}, executing on it...",
-                    &self.service.bright_green()
-                );
-                let result = component.start(&ws, &global_options);
-
-                match result {
-                    Err(err) => {
-                        println!("{} {}", "Failed to execute on component:".bright_red(), err)
-                    }
-                    Ok(t) => {
-                        if let Some(out) = t {
-                            println!("{}", out.green());
-                        }
-                    }
-                }
-            }
+        ws.init(&global_options)?;
+
+        let target = ws.resolve_target(&self.service)?;
+        let mut component = ws.components.get(&target.component).unwrap().clone();
+
+        if component.config.is_template.unwrap_or(false) {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                format!(
+                    "You can't execute on a service template, please use instances: {}",
+                    ws.get_executable_component_names().join(",")
+                ),
+            )));
         }
 
+        println!(
+            "Found component {}, executing on it...",
+            &self.service.bright_green()
+        );
+
+        let mut options = global_options.clone();
+        options.cmd = Some(self.command.clone());
+        options.working_dir = self.workdir.clone();
+        options.service = target.service;
+
+        component.exec_custom_command(&mut ws, &options)?;
+
         Ok(None)
     }
 }
@@ -0,0 +1,238 @@
+use clap::Args;
+use colored::{Color, Colorize};
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::{exec_shell_prefixed, shell_quote, subst_vars, GlobalOptions, ShellError},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+const PREFIX_COLORS: [Color; 6] = [
+    Color::Cyan,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::BrightCyan,
+];
+
+#[derive(Debug, Args)]
+pub struct ForeachCommand {
+    /// How many directories to run the command in at once
+    #[arg(long, default_value_t = 1)]
+    parallel: usize,
+
+    /// Skip packages, only run in component directories
+    #[arg(long)]
+    no_packages: bool,
+
+    /// Host command, its arguments are passed on as they are
+    #[clap(
+        num_args = 1..,
+        required = true,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    command: Vec<String>,
+}
+
+/// Directory the command runs in, with the env it runs with
+struct Entry {
+    name: String,
+    path: String,
+    env: HashMap<String, String>,
+}
+
+struct Outcome {
+    index: usize,
+    name: String,
+    duration: Duration,
+    error: Option<String>,
+}
+
+impl ExecuteTrait for ForeachCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let tag = global_options.tag.as_deref();
+
+        let mut entries = Vec::new();
+
+        // Only components and packages with the global `--tag`
+        for name in ws.get_executable_component_names() {
+            let component = ws.components.get(&name).unwrap();
+
+            if tag.is_some_and(|tag| !component.has_tag(tag)) {
+                continue;
+            }
+
+            let env = component.compose_env();
+            let path = env.get("SVC_PATH").cloned().unwrap_or_default();
+
+            entries.push(Entry { name, path, env });
+        }
+
+        if !self.no_packages {
+            for (name, package) in &ws.config.as_ref().unwrap().packages {
+                let tags = package.tags.as_deref().unwrap_or_default();
+
+                if tag.is_some_and(|tag| !tags.iter().any(|t| t == tag)) {
+                    continue;
+                }
+
+                // Packages get the workspace context, named like components
+                let mut env: HashMap<String, String> = ws
+                    .context
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+                let path = subst_vars(&package.path, &ws.context);
+                env.insert("APP_NAME".to_string(), name.clone());
+                env.insert("SVC_PATH".to_string(), path.clone());
+
+                entries.push(Entry {
+                    name: name.clone(),
+                    path,
+                    env,
+                });
+            }
+        }
+
+        let (entries, missing): (Vec<Entry>, Vec<Entry>) = entries
+            .into_iter()
+            .partition(|entry| Path::new(&entry.path).is_dir());
+
+        for entry in &missing {
+            println!(
+                "{} {} ({}) is not checked out, skipping it",
+                "Warning:".yellow(),
+                entry.name,
+                entry.path
+            );
+        }
+
+        if entries.is_empty() {
+            return Err(Box::new(CliError::new(
+                "No directories to run the command in",
+            )));
+        }
+
+        let command = self
+            .command
+            .iter()
+            .map(|arg| shell_quote(arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if global_options.dry_run {
+            for entry in &entries {
+                println!("{} $ {}", entry.path, command);
+            }
+
+            return Ok(None);
+        }
+
+        let width = entries
+            .iter()
+            .map(|entry| entry.name.len())
+            .max()
+            .unwrap_or(0);
+        let queue: VecDeque<(usize, Entry)> = entries.into_iter().enumerate().collect();
+        let total = queue.len();
+        let queue = Arc::new(Mutex::new(queue));
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
+
+        let workers = (0..self.parallel.clamp(1, total))
+            .map(|_| {
+                let queue = queue.clone();
+                let outcomes = outcomes.clone();
+                let command = command.clone();
+
+                std::thread::spawn(move || loop {
+                    let next = queue.lock().unwrap().pop_front();
+                    let (index, entry) = match next {
+                        Some(next) => next,
+                        None => break,
+                    };
+
+                    let prefix = format!("{:width$} |", entry.name, width = width)
+                        .color(PREFIX_COLORS[index % PREFIX_COLORS.len()])
+                        .to_string();
+
+                    let started = Instant::now();
+                    let result = exec_shell_prefixed(&command, &entry.path, &entry.env, &prefix);
+
+                    let error = result
+                        .err()
+                        .map(|err| match err.downcast_ref::<ShellError>() {
+                            Some(err) => format!("exit code {}", err.err_code),
+                            None => err.to_string(),
+                        });
+
+                    outcomes.lock().unwrap().push(Outcome {
+                        index,
+                        name: entry.name,
+                        duration: started.elapsed(),
+                        error,
+                    });
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for worker in workers {
+            let _ = worker.join();
+        }
+
+        // Summary in selection order, whichever finished first
+        let mut outcomes = outcomes.lock().unwrap();
+        outcomes.sort_by_key(|outcome| outcome.index);
+        let failed: Vec<&Outcome> = outcomes.iter().filter(|o| o.error.is_some()).collect();
+
+        println!();
+        for outcome in outcomes.iter() {
+            match &outcome.error {
+                Some(error) => println!(
+                    "{} {} ({}, {:.1}s)",
+                    "✗".bright_red(),
+                    outcome.name,
+                    error,
+                    outcome.duration.as_secs_f64()
+                ),
+                None => println!(
+                    "{} {} ({:.1}s)",
+                    "✓".green(),
+                    outcome.name,
+                    outcome.duration.as_secs_f64()
+                ),
+            }
+        }
+
+        if !failed.is_empty() {
+            return Err(Box::new(CliError::new(format!(
+                "Failed in {} of {}: {}",
+                failed.len(),
+                total,
+                failed
+                    .iter()
+                    .map(|outcome| outcome.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))));
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,75 @@
+use clap::{Args, CommandFactory, Parser};
+use std::error::Error;
+
+use prettytable::{format, Table};
+
+use crate::{
+    args::{AppArgs, ExecuteTrait},
+    core::GlobalOptions,
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct HelpCommand {
+    /// Subcommand to describe, or a component to list the custom commands of
+    names: Vec<String>,
+}
+
+impl ExecuteTrait for HelpCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let is_subcommand = match self.names.first() {
+            Some(name) => AppArgs::command().find_subcommand(name).is_some(),
+            None => true,
+        };
+
+        // Same output as --help of the subcommand
+        if is_subcommand {
+            let args = std::iter::once("uniform".to_string())
+                .chain(self.names.iter().cloned())
+                .chain(std::iter::once("--help".to_string()));
+
+            if let Err(err) = AppArgs::try_parse_from(args) {
+                err.print()?;
+            }
+
+            return Ok(None);
+        }
+
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let name = ws.resolve_name(&self.names[0])?;
+        let component = ws.components.get(&name).unwrap();
+        let commands = component.get_commands();
+
+        if commands.is_empty() {
+            println!("Component {} has no commands", name);
+            return Ok(None);
+        }
+
+        println!("Usage: uniform {} <COMMAND> [ARGS]...\n", name);
+
+        let mut table = Table::new();
+        table.set_titles(row!["Command", "Description", "Runs"]);
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        for (command, custom) in &commands {
+            table.add_row(row![
+                command,
+                custom.description().unwrap_or_default(),
+                custom.command()
+            ]);
+        }
+
+        table.printstd();
+
+        Ok(None)
+    }
+}
@@ -1,4 +1,19 @@
 pub mod start;
 pub mod stop;
 pub mod exec;
-pub mod ps;
\ No newline at end of file
+pub mod ps;
+pub mod top;
+pub mod urls;
+pub mod dns;
+pub mod down;
+pub mod proxy;
+pub mod proxy_config;
+pub mod run;
+pub mod run_once;
+pub mod foreach;
+pub mod env;
+pub mod shell;
+pub mod cp;
+pub mod watch;
+pub mod r#do;
+pub mod help;
\ No newline at end of file
@@ -0,0 +1,50 @@
+use clap::Args;
+use colored::Colorize;
+use std::{error::Error, sync::Arc};
+
+use tokio::runtime::Runtime;
+
+use crate::{
+    args::ExecuteTrait, core::GlobalOptions, engine::create_engine, proxy::ProxyServer,
+    settings::Settings, workspace::create_workspace,
+};
+
+/// Runs a development reverse proxy routing component hostnames to their containers
+#[derive(Debug, Args)]
+pub struct ProxyCommand {
+    /// Address to listen on, port 80 needs root on most systems
+    #[arg(short, long, default_value = "127.0.0.1:8880")]
+    listen: String,
+}
+
+impl ExecuteTrait for ProxyCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let server = Arc::new(ProxyServer::new(&ws, create_engine(global_options))?);
+
+        let port = self.listen.rsplit_once(':').map(|(_, port)| port);
+
+        for route in &server.routes {
+            println!(
+                "{} -> {}",
+                route.proxy_url(port).bright_green(),
+                route.component
+            );
+        }
+        println!("Listening on {}", self.listen.bright_blue());
+
+        let rt = Runtime::new()?;
+        rt.block_on(server.run(&self.listen))
+            .map_err(|err| err as Box<dyn Error>)?;
+
+        Ok(None)
+    }
+}
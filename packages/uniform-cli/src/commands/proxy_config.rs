@@ -0,0 +1,53 @@
+use clap::{builder::PossibleValuesParser, Args};
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::ExecuteTrait,
+    core::GlobalOptions,
+    proxy::{collect_routes, render, PROXY_FORMATS},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+/// Generates a reverse proxy config routing every component hostname
+#[derive(Debug, Args)]
+pub struct ProxyConfigCommand {
+    #[arg(long, default_value = "nginx", value_parser = PossibleValuesParser::new(PROXY_FORMATS))]
+    format: String,
+
+    /// Write the config to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<String>,
+}
+
+impl ExecuteTrait for ProxyConfigCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let routes = collect_routes(&ws)?;
+        let config = render(&self.format, &routes)?;
+
+        match &self.output {
+            Some(output) => {
+                std::fs::write(output, config)?;
+                println!(
+                    "Wrote {} config with {} routes to {}",
+                    self.format,
+                    routes.len(),
+                    output.bright_green()
+                );
+            }
+            None => print!("{}", config),
+        }
+
+        Ok(None)
+    }
+}
@@ -1,7 +1,5 @@
 use clap::{Args, Parser, Subcommand};
 use colored::Colorize;
-use futures::{future::ok, stream::FuturesUnordered};
-use futures::{FutureExt, StreamExt};
 use std::{error::Error, path::PathBuf};
 
 use prettytable::{
@@ -11,15 +9,32 @@ use prettytable::{
 
 use crate::{
     args::{CliError, ExecuteTrait},
-    core::{exec_shell_to_string, GlobalOptions, SystemPath},
+    component::Component,
+    core::{GlobalOptions, SystemPath},
+    engine::{create_engine, ContainerInfo},
+    proxy::to_url,
     settings::Settings,
+    state::WorkspaceState,
+    status::WorkspaceStatus,
     workspace::create_workspace,
 };
 
 use tokio::runtime::Runtime;
 
 #[derive(Debug, Args)]
-pub struct PsCommand {}
+pub struct PsCommand {
+    /// Only show components with at least one running service
+    #[arg(long)]
+    running: bool,
+
+    /// Show templates as well
+    #[arg(long)]
+    all: bool,
+
+    /// Show image and container id columns
+    #[arg(long)]
+    wide: bool,
+}
 
 impl ExecuteTrait for PsCommand {
     fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
@@ -33,55 +48,97 @@ impl ExecuteTrait for PsCommand {
         let ws_load = ws.load()?;
         ws.init(&global_options)?;
 
+        let state = WorkspaceState::load(&ws)?;
+
+        let rt = Runtime::new()?;
+        let engine = create_engine(global_options);
+        let status = rt
+            .block_on(WorkspaceStatus::query(engine.as_ref(), &ws))
+            .map_err(|err| err as Box<dyn Error>)?;
+
         let mut table = Table::new();
 
-        table.add_row(row!["Name", "Status", "Container ID"]);
+        if self.wide {
+            table.set_titles(row![
+                "Name", "Service", "Status", "Health", "Uptime", "Ports", "URL", "Image",
+                "Container ID"
+            ]);
+        } else {
+            table.set_titles(row![
+                "Name", "Service", "Status", "Health", "Uptime", "Ports", "URL"
+            ]);
+        }
         table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
 
-        let mut rt = Runtime::new()?;
-        let mut tasks = FuturesUnordered::new();
+        for component in ws.components.values() {
+            let is_template = component.config.is_template.unwrap_or(false);
 
-        for (k, v) in ws.components.iter() {
-            let ws = ws.clone();
-            let global_options = global_options.clone();
+            if is_template && !self.all {
+                continue;
+            }
 
-            let task = async move {
-                let comp = ws.find_executable_component(k);
+            if let Some(tag) = &global_options.tag {
+                if !component.has_tag(tag) {
+                    continue;
+                }
+            }
 
-                match comp {
-                    Some(component) => {
-                        let id = component.get_container_id(&ws, &global_options);
+            if self.running && !status.is_running(&component.name) {
+                continue;
+            }
 
-                        match id {
-                            Ok(id) => {
-                                let status = if id.is_empty() { "Exited" } else { "Running" };
-                                let short_id = if id.is_empty() { "" } else { &id[..12] };
+            if is_template {
+                table.add_row(self.make_row(component.name.clone(), "", "Template", None, ""));
+                continue;
+            }
 
-                                Ok(row![k, status, short_id])
-                            }
-                            Err(_) => Ok(row![]),
-                        }
-                    }
-                    None => Ok(row![]),
-                }
-            };
+            if let Some(host) = &component.config.hosted_in {
+                let status = if status.is_running(host) {
+                    format!("Running (in {})", host)
+                } else {
+                    format!("Exited (in {})", host)
+                };
+                let url = component
+                    .get_hostname()
+                    .map(|hostname| to_url(&hostname))
+                    .unwrap_or_default();
 
-            tasks.push(task);
-        }
+                table.add_row(self.make_row(component.name.clone(), "", &status, None, &url));
+                continue;
+            }
 
-        let results: Vec<Result<Row, Box<dyn Error + Send + Sync>>> =
-            rt.block_on(async { tasks.collect().await });
+            let stale = state.is_stale(component);
+            let url = component
+                .get_hostname()
+                .map(|hostname| to_url(&hostname))
+                .unwrap_or_default();
 
-        for result in results {
-            match result {
-                Ok(row) => {
-                    if !row.is_empty() {
-                        table.add_row(row);
+            let declared = component.get_services().unwrap_or_default();
+            let services = status.services(&component.name, &declared);
+
+            if services.is_empty() {
+                table.add_row(self.make_row(component.name.clone(), "", "Exited", None, &url));
+                continue;
+            }
+
+            for (i, (service, container)) in services.into_iter().enumerate() {
+                let name = if i == 0 {
+                    component.name.clone()
+                } else {
+                    String::new()
+                };
+                let url = if i == 0 { url.as_str() } else { "" };
+
+                let status = match container {
+                    None => "Exited".to_string(),
+                    Some(container) if container.is_running() && stale => {
+                        "Running (stale)".to_string()
                     }
-                }
-                Err(e) => {
-                    println!("Error: {:?}", e)
-                }
+                    Some(container) if container.is_running() => "Running".to_string(),
+                    Some(container) => capitalize(&container.state),
+                };
+
+                table.add_row(self.make_row(name, &service, &status, container, url));
             }
         }
 
@@ -90,3 +147,142 @@ impl ExecuteTrait for PsCommand {
         Ok(None)
     }
 }
+
+impl PsCommand {
+    fn make_row(
+        &self,
+        name: String,
+        service: &str,
+        status: &str,
+        container: Option<&ContainerInfo>,
+        url: &str,
+    ) -> Row {
+        let health = container.and_then(|c| c.health()).unwrap_or("");
+        let uptime = container.and_then(|c| c.uptime()).unwrap_or("");
+        let ports = container.map(|c| short_ports(&c.ports)).unwrap_or_default();
+
+        if self.wide {
+            let image = container.map(|c| c.image.clone()).unwrap_or_default();
+            let id = container.map(|c| c.short_id()).unwrap_or_default();
+
+            row![name, service, status, health, uptime, ports, url, image, id]
+        } else {
+            row![name, service, status, health, uptime, ports, url]
+        }
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+    }
+}
+
+// "0.0.0.0:8080->80/tcp, :::8080->80/tcp" => "8080->80/tcp"
+fn short_ports(ports: &str) -> String {
+    let mut result: Vec<&str> = Vec::new();
+
+    for port in ports.split(", ").filter(|p| !p.is_empty()) {
+        let port = match port.find("->") {
+            Some(arrow) => {
+                let host = &port[..arrow];
+                let host_port_start = host.rfind(':').map(|i| i + 1).unwrap_or(0);
+                &port[host_port_start..]
+            }
+            None => port,
+        };
+
+        if !result.contains(&port) {
+            result.push(port);
+        }
+    }
+
+    result.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::Engine;
+    use crate::fake_engine::FakeEngine;
+
+    fn cells(row: &Row) -> Vec<String> {
+        row.iter().map(|cell| cell.get_content()).collect()
+    }
+
+    fn container(running: bool) -> ContainerInfo {
+        let engine = FakeEngine::new();
+        engine.add_container("shop", "backend", "app", running);
+
+        Runtime::new()
+            .unwrap()
+            .block_on(engine.list_containers("shop"))
+            .unwrap()
+            .remove(0)
+    }
+
+    fn command(wide: bool) -> PsCommand {
+        PsCommand {
+            running: false,
+            all: false,
+            wide,
+        }
+    }
+
+    #[test]
+    fn row_of_running_container() {
+        let container = container(true);
+        let row = command(false).make_row(
+            "backend".to_string(),
+            "app",
+            "Running",
+            Some(&container),
+            "https://backend.localhost",
+        );
+
+        assert_eq!(
+            cells(&row),
+            vec![
+                "backend",
+                "app",
+                "Running",
+                "",
+                "1 second",
+                "8080->80/tcp",
+                "https://backend.localhost"
+            ]
+        );
+    }
+
+    #[test]
+    fn wide_row_of_exited_container() {
+        let container = container(false);
+        let row = command(true).make_row(String::new(), "app", "Exited", Some(&container), "");
+
+        assert_eq!(
+            cells(&row),
+            vec![
+                "",
+                "app",
+                "Exited",
+                "",
+                "",
+                "",
+                "",
+                "backend:latest",
+                "fake00000000"
+            ]
+        );
+    }
+
+    #[test]
+    fn short_ports_drop_host_addresses() {
+        assert_eq!(
+            short_ports("0.0.0.0:8080->80/tcp, :::8080->80/tcp, 9000/tcp"),
+            "8080->80/tcp, 9000/tcp"
+        );
+    }
+}
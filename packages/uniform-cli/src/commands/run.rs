@@ -0,0 +1,103 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+use std::time::Duration;
+
+use indexmap::IndexMap;
+use prettytable::{format, Table};
+
+use crate::{
+    args::ExecuteTrait, core::GlobalOptions, script::ScriptRunner, settings::Settings,
+    workspace::create_workspace, workspace_config::ScriptStep,
+};
+
+#[derive(Debug, Args)]
+pub struct RunCommand {
+    /// Script to run, lists the scripts of the workspace if not set
+    script: Option<String>,
+
+    /// Arguments available to the script as ${1}, ${2}.. and ${@}
+    #[clap(trailing_var_arg = true, allow_hyphen_values = true)]
+    args: Vec<String>,
+}
+
+impl ExecuteTrait for RunCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let script = match &self.script {
+            Some(script) => script,
+            None => {
+                print_scripts(&ws.config.as_ref().unwrap().scripts);
+                return Ok(None);
+            }
+        };
+
+        let mut runner = ScriptRunner::new(&ws, global_options);
+        let result = runner.run(script, &self.args);
+
+        if runner.timings.is_empty() {
+            return result.map(|_| None);
+        }
+
+        let mut table = Table::new();
+        table.set_titles(row!["Step", "Time", "Result"]);
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        for timing in &runner.timings {
+            let status = if timing.success {
+                "ok".green()
+            } else {
+                "failed".bright_red()
+            };
+
+            table.add_row(row![
+                timing.label,
+                format!("{:.1}s", timing.duration.as_secs_f64()),
+                status
+            ]);
+        }
+
+        let total: Duration = runner.timings.iter().map(|timing| timing.duration).sum();
+
+        println!();
+        table.printstd();
+        println!("Total {:.1}s", total.as_secs_f64());
+
+        result.map(|_| None)
+    }
+}
+
+fn print_scripts(scripts: &IndexMap<String, Vec<ScriptStep>>) {
+    if scripts.is_empty() {
+        println!("No scripts declared in the workspace");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_titles(row!["Script", "Steps"]);
+    table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+    for (name, steps) in scripts {
+        let steps = steps
+            .iter()
+            .map(|step| match step {
+                ScriptStep::Shell(command) => format!("$ {}", command),
+                ScriptStep::Uniform { uniform } => format!("uniform {}", uniform),
+                ScriptStep::Script { script } => format!("run {}", script),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        table.add_row(row![name, steps]);
+    }
+
+    table.printstd();
+}
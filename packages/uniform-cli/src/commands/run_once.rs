@@ -0,0 +1,98 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::GlobalOptions,
+    plan::Plan,
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct RunOnceCommand {
+    /// Component to run, optionally with a compose service as component:service
+    target: String,
+
+    /// Working dir inside the container, defaults to the component's exec_path
+    #[arg(short, long)]
+    workdir: Option<String>,
+
+    #[clap(
+        num_args = 1..,
+        required = true,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    command: Vec<String>,
+}
+
+impl ExecuteTrait for RunOnceCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let target = ws.resolve_target(&self.target)?;
+        let component = ws.components.get(&target.component).unwrap();
+
+        if component.config.is_template.unwrap_or(false) {
+            return Err(Box::new(CliError::new(format!(
+                "You can't run a service template, please use instances: {}",
+                ws.get_executable_component_names().join(",")
+            ))));
+        }
+
+        // The one-off container needs what the component needs, a hosted
+        // component also what its host needs
+        let mut plan = Plan::dependencies(&ws, &target.component, global_options)?;
+
+        if let Some(host) = &component.config.hosted_in {
+            let host_plan = Plan::dependencies(&ws, host, global_options)?;
+
+            for step in host_plan.steps {
+                let planned = plan
+                    .steps
+                    .iter()
+                    .any(|planned| planned.component == step.component);
+
+                if !planned && step.component != target.component {
+                    plan.steps.push(step);
+                }
+            }
+
+            // Missing resources are the same for both, listed if either starts anything
+            if plan.networks.is_empty() && plan.volumes.is_empty() {
+                plan.networks = host_plan.networks;
+                plan.volumes = host_plan.volumes;
+            }
+        }
+
+        if global_options.dry_run {
+            plan.print(global_options)?;
+        } else {
+            plan.execute(&ws, global_options)?;
+        }
+
+        println!(
+            "Running {} in a one-off container of {}",
+            self.command.join(" ").bright_cyan(),
+            target.component.bright_green()
+        );
+
+        let mut options = global_options.clone();
+        options.cmd = Some(self.command.clone());
+        options.working_dir = self.workdir.clone();
+        options.service = target.service;
+
+        component.run_once(&ws, &options)?;
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,135 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::{
+    args::ExecuteTrait,
+    core::{shell_quote, GlobalOptions},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+/// Set inside the shell to the workspace, or workspace:component, it was started for
+const SHELL_MARKER: &str = "UNIFORM_SHELL";
+
+#[derive(Debug, Args)]
+pub struct ShellCommand {
+    /// Component to export the context of, the workspace one if not set
+    component: Option<String>,
+}
+
+impl ExecuteTrait for ShellCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let context = ws.get_context(self.component.as_deref())?;
+        let workspace_name = &ws.config.as_ref().unwrap().name;
+
+        let label = match &self.component {
+            Some(component) => format!("{}:{}", workspace_name, ws.resolve_name(component)?),
+            None => workspace_name.clone(),
+        };
+
+        if let Ok(current) = std::env::var(SHELL_MARKER) {
+            println!(
+                "{} already inside the uniform shell of {}",
+                "Warning:".yellow(),
+                current
+            );
+        }
+
+        let shell = user_shell();
+
+        if global_options.dry_run {
+            println!("Would start {} with the context of {}", shell, label);
+            return Ok(None);
+        }
+
+        let mut command = Command::new(&shell);
+        command.envs(&context).env(SHELL_MARKER, &label);
+
+        let marker = format!("(uniform:{}) ", label);
+        let shell_dir = ws.home_path().join("shell");
+        let shell_name = Path::new(&shell)
+            .file_stem()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        // rc files set their own prompt, so the marker is added after them
+        match shell_name.as_str() {
+            "bash" => {
+                let rcfile = shell_dir.join("bashrc");
+                fs::create_dir_all(&shell_dir)?;
+                fs::write(
+                    &rcfile,
+                    format!(
+                        "[ -f ~/.bashrc ] && . ~/.bashrc\nPS1={}\"$PS1\"\n",
+                        shell_quote(&marker)
+                    ),
+                )?;
+
+                command.arg("--rcfile").arg(rcfile);
+            }
+            "zsh" => {
+                let zdotdir = shell_dir.join("zsh");
+                fs::create_dir_all(&zdotdir)?;
+                fs::write(
+                    zdotdir.join(".zshenv"),
+                    "[ -f \"$UNIFORM_ZDOTDIR/.zshenv\" ] && . \"$UNIFORM_ZDOTDIR/.zshenv\"\n",
+                )?;
+                fs::write(
+                    zdotdir.join(".zshrc"),
+                    format!(
+                        "ZDOTDIR=\"$UNIFORM_ZDOTDIR\"\n[ -f \"$ZDOTDIR/.zshrc\" ] && . \"$ZDOTDIR/.zshrc\"\nPROMPT={}\"$PROMPT\"\n",
+                        shell_quote(&marker)
+                    ),
+                )?;
+
+                let original = std::env::var("ZDOTDIR")
+                    .or_else(|_| std::env::var("HOME"))
+                    .unwrap_or_default();
+
+                command
+                    .env("UNIFORM_ZDOTDIR", original)
+                    .env("ZDOTDIR", zdotdir);
+            }
+            _ => {
+                let prompt = std::env::var("PS1").unwrap_or_else(|_| "$ ".to_string());
+                command.env("PS1", format!("{}{}", marker, prompt));
+            }
+        }
+
+        println!(
+            "Entering the uniform shell of {}, exit to leave",
+            label.bright_green()
+        );
+
+        // Exit status of the shell is that of its last command, not an error
+        command.status()?;
+
+        println!("Left the uniform shell of {}", label.bright_green());
+
+        Ok(None)
+    }
+}
+
+fn user_shell() -> String {
+    if cfg!(target_family = "windows") {
+        return std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string());
+    }
+
+    std::env::var("SHELL")
+        .ok()
+        .filter(|shell| !shell.is_empty())
+        .unwrap_or_else(|| "sh".to_string())
+}
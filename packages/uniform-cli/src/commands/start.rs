@@ -5,13 +5,18 @@ use std::{error::Error, path::PathBuf};
 use crate::{
     args::{CliError, ExecuteTrait},
     core::{path_to_unix, wsl_path, GlobalOptions, SystemPath},
+    plan::Plan,
     workspace::create_workspace, settings::Settings,
 };
 
 #[derive(Debug, Args)]
 pub struct StartCommand {
-    #[clap(value_delimiter = ' ', num_args = 1.., required = true)]
+    #[clap(value_delimiter = ' ', num_args = 1.., required_unless_present = "changed")]
     services: Vec<String>,
+
+    /// Recreate running components whose config changed since they were started
+    #[arg(long)]
+    changed: bool,
 }
 
 impl ExecuteTrait for StartCommand {
@@ -29,50 +34,79 @@ impl ExecuteTrait for StartCommand {
             return Err(ws_load.unwrap_err());
         }
 
-        ws.init(&global_options);
+        ws.init(&global_options)?;
 
-        for comp_name in &self.services {
-            let comp = ws.components.get(comp_name);
+        let targets = self
+            .services
+            .iter()
+            .map(|target| ws.resolve_target(target))
+            .collect::<Result<Vec<_>, _>>()?;
 
-            match comp {
-                None => {
-                    return Err(Box::new(CliError::new(format!(
-                        "Unknown service '{:?}'. Possible services are: {:?}",
-                        comp_name,
-                        ws.get_executable_component_names()
-                    ))));
-                }
-                Some(component) => {
-                    if component.config.is_template.unwrap_or(false) {
-                        return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!(
-                                "You can't start a service template, please use instances: {}",
-                                ws.get_executable_component_names().join(",")
-                            ),
-                        )));
-                    }
+        if self.changed {
+            let names: Vec<String> = targets.iter().map(|t| t.component.clone()).collect();
+            let plan = Plan::changed(&ws, &names, global_options)?;
+
+            if global_options.dry_run {
+                plan.print(global_options)?;
+            } else if plan.steps.is_empty() {
+                println!("{}", "Nothing changed".green());
+            } else {
+                plan.execute(&ws, global_options)?;
+            }
+
+            return Ok(None);
+        }
+
+        if global_options.dry_run {
+            let plan = Plan::start(&ws, &targets, global_options)?;
+            plan.print(global_options)?;
+
+            return Ok(None);
+        }
+
+        let mut failed = Vec::new();
+
+        for target in &targets {
+            let comp_name = &target.component;
+            let component = ws.components.get(comp_name).unwrap();
+
+            if component.config.is_template.unwrap_or(false) {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!(
+                        "You can't start a service template, please use instances: {}",
+                        ws.get_executable_component_names().join(",")
+                    ),
+                )));
+            }
 
-                    println!(
-                        "Found component {}, starting it...",
-                        comp_name.bright_green()
-                    );
-                    let result = component.start(&ws, &global_options);
-
-                    match result {
-                        Err(err) => {
-                            println!("{} {:#?}", "Failed to start component:".bright_red(), err)
-                        }
-                        Ok(t) => {
-                            if let Some(out) = t {
-                                println!("{}", out.green());
-                            }
-                        }
+            println!(
+                "Found component {}, starting it...",
+                comp_name.bright_green()
+            );
+            let result =
+                component.start_service(&ws, target.service.as_deref(), global_options);
+
+            match result {
+                Err(err) => {
+                    println!("{} {:#?}", "Failed to start component:".bright_red(), err);
+                    failed.push(comp_name.clone());
+                }
+                Ok(t) => {
+                    if let Some(out) = t {
+                        println!("{}", out.green());
                     }
                 }
             }
         }
 
+        if !failed.is_empty() {
+            return Err(Box::new(CliError::new(format!(
+                "Failed to start {}",
+                failed.join(", ")
+            ))));
+        }
+
         Ok(None)
     }
 }
@@ -5,6 +5,7 @@ use std::{error::Error, path::PathBuf};
 use crate::{
     args::{CliError, ExecuteTrait},
     core::{path_to_unix, wsl_path, GlobalOptions, SystemPath},
+    plan::Plan,
     settings::Settings,
     workspace::create_workspace,
 };
@@ -30,51 +31,65 @@ impl ExecuteTrait for StopCommand {
             return Err(ws_load.unwrap_err());
         }
 
-        ws.init(&global_options);
+        ws.init(&global_options)?;
 
-        for comp_name in &self.services {
-            let comp = ws.components.get(comp_name);
+        let targets = self
+            .services
+            .iter()
+            .map(|target| ws.resolve_target(target))
+            .collect::<Result<Vec<_>, _>>()?;
 
-            match comp {
-                None => {
-                    return Err(Box::new(CliError::new(format!(
-                        "Unknown service '{:?}'. Possible services are: {:?}",
-                        comp_name,
-                        ws.get_executable_component_names()
-                    ))));
-                }
-                Some(component) => {
-                    if component.config.is_template.unwrap_or(false) {
-                        return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!(
-                                "You can't stop a service template, please use instances: {}",
-                                ws.get_executable_component_names().join(",")
-                            ),
-                        )));
-                    }
+        if global_options.dry_run {
+            let plan = Plan::stop(&ws, &targets, global_options)?;
+            plan.print(global_options)?;
+
+            return Ok(None);
+        }
+
+        let mut failed = Vec::new();
+
+        for target in &targets {
+            let comp_name = &target.component;
+            let component = ws.components.get(comp_name).unwrap();
 
-                    println!(
-                        "Found component {}, {:?}, stopping it...",
-                        comp_name.green(),
-                        component.config.is_template
-                    );
-                    let result = component.stop(&ws, &global_options);
-
-                    match result {
-                        Err(err) => {
-                            println!("{} {}", "Failed to stop component:".bright_red(), err)
-                        }
-                        Ok(t) => {
-                            if let Some(out) = t {
-                                println!("{}", out.green());
-                            }
-                        }
+            if component.config.is_template.unwrap_or(false) {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!(
+                        "You can't stop a service template, please use instances: {}",
+                        ws.get_executable_component_names().join(",")
+                    ),
+                )));
+            }
+
+            println!(
+                "Found component {}, {:?}, stopping it...",
+                comp_name.green(),
+                component.config.is_template
+            );
+            let result =
+                component.stop_service(&ws, target.service.as_deref(), global_options);
+
+            match result {
+                Err(err) => {
+                    println!("{} {}", "Failed to stop component:".bright_red(), err);
+                    failed.push(comp_name.clone());
+                }
+                Ok(t) => {
+                    if let Some(out) = t {
+                        println!("{}", out.green());
                     }
                 }
             }
         }
 
+        if !failed.is_empty() {
+            return Err(Box::new(CliError::new(format!(
+                "Failed to stop {}",
+                failed.join(", ")
+            ))));
+        }
+
         Ok(None)
     }
 }
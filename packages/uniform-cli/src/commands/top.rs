@@ -0,0 +1,535 @@
+use clap::Args;
+use colored::Colorize;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState},
+    Frame, Terminal,
+};
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{self, Stdout},
+    sync::{mpsc, Arc, Mutex},
+    time::Duration,
+};
+use tokio::{runtime::Runtime, sync::Notify};
+
+use crate::{
+    args::ExecuteTrait,
+    core::GlobalOptions,
+    engine::{create_engine, ContainerInfo, ContainerStats, Engine},
+    settings::Settings,
+    status::WorkspaceStatus,
+    workspace::{create_workspace, Workspace},
+};
+
+const LOG_LINES: usize = 200;
+
+#[derive(Debug, Args)]
+pub struct TopCommand {
+    /// Refresh interval in seconds
+    #[arg(long, default_value_t = 2)]
+    interval: u64,
+}
+
+enum Message {
+    Snapshot(WorkspaceStatus, HashMap<String, ContainerStats>),
+    Logs(Vec<String>),
+    Info(String),
+}
+
+struct TopRow {
+    component: String,
+    service: String,
+    container: Option<ContainerInfo>,
+}
+
+struct App {
+    /// Compose services of every executable component, in workspace order
+    services: Vec<(String, Vec<String>)>,
+    rows: Vec<TopRow>,
+    stats: HashMap<String, ContainerStats>,
+    table_state: TableState,
+    show_logs: bool,
+    logs: Vec<String>,
+    message: String,
+}
+
+impl ExecuteTrait for TopCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let rt = Runtime::new()?;
+        let engine = create_engine(global_options);
+
+        let mut terminal = enter_tui()?;
+        let result = self.run(&mut terminal, &rt, engine, ws, global_options);
+        leave_tui(&mut terminal)?;
+
+        result?;
+
+        Ok(None)
+    }
+}
+
+impl TopCommand {
+    fn run(
+        &self,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        rt: &Runtime,
+        engine: Arc<dyn Engine>,
+        ws: Workspace,
+        options: &GlobalOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        let (tx, rx) = mpsc::channel();
+        let refresh = Arc::new(Notify::new());
+        let logs_target: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+        let mut app = App::new(&ws);
+
+        rt.spawn(refresh_loop(
+            engine.clone(),
+            ws.clone(),
+            Duration::from_secs(self.interval),
+            tx.clone(),
+            refresh.clone(),
+            logs_target.clone(),
+        ));
+
+        loop {
+            while let Ok(message) = rx.try_recv() {
+                app.apply(message);
+            }
+
+            terminal.draw(|f| app.draw(f))?;
+
+            if !event::poll(Duration::from_millis(200))? {
+                continue;
+            }
+
+            let key = match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => key,
+                _ => continue,
+            };
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Down | KeyCode::Char('j') => app.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => app.select_previous(),
+                KeyCode::Char(action @ ('s' | 'x' | 'r')) => {
+                    if let Some(name) = app.selected().map(|row| row.component.clone()) {
+                        let engine = engine.clone();
+                        let tx = tx.clone();
+                        let refresh = refresh.clone();
+
+                        app.message = format!("{} {}...", action_name(action), name);
+
+                        rt.spawn(async move {
+                            let result = match action {
+                                's' => engine.start_component(&name).await,
+                                'x' => engine.stop_component(&name).await,
+                                _ => engine.restart_component(&name).await,
+                            };
+
+                            let message = match result {
+                                Ok(()) => format!("{} {}: done", action_name(action), name),
+                                Err(err) => format!("{} {}: {}", action_name(action), name, err),
+                            };
+
+                            let _ = tx.send(Message::Info(message));
+                            refresh.notify_one();
+                        });
+                    }
+                }
+                KeyCode::Char('l') => {
+                    app.show_logs = !app.show_logs;
+                    app.logs.clear();
+                }
+                KeyCode::Char('e') => {
+                    let selected = app.selected().and_then(|row| {
+                        Some((ws.components.get(&row.component)?, row.container.as_ref()?))
+                    });
+
+                    match selected {
+                        // Same user and working dir as `uniform exec`
+                        Some((component, container)) if container.is_running() => {
+                            match component.get_user(options) {
+                                Ok(user) => {
+                                    let working_dir = component.get_exec_dir(&ws.cwd);
+
+                                    leave_tui(terminal)?;
+                                    let result = engine.exec_shell(
+                                        &container.id,
+                                        &user,
+                                        working_dir.as_deref(),
+                                    );
+                                    resume_tui(terminal)?;
+
+                                    if let Err(err) = result {
+                                        app.message = format!("exec failed: {}", err);
+                                    }
+                                }
+                                Err(err) => app.message = format!("exec failed: {}", err),
+                            }
+                        }
+                        _ => app.message = "Selected service is not running".to_string(),
+                    }
+                }
+                _ => {}
+            }
+
+            *logs_target.lock().unwrap() = if app.show_logs {
+                app.selected()
+                    .and_then(|row| row.container.as_ref())
+                    .map(|container| container.id.clone())
+            } else {
+                None
+            };
+
+            if app.show_logs {
+                refresh.notify_one();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn refresh_loop(
+    engine: Arc<dyn Engine>,
+    ws: Workspace,
+    interval: Duration,
+    tx: mpsc::Sender<Message>,
+    refresh: Arc<Notify>,
+    logs_target: Arc<Mutex<Option<String>>>,
+) {
+    loop {
+        match WorkspaceStatus::query(engine.as_ref(), &ws).await {
+            Ok(status) => {
+                let running: Vec<String> = status
+                    .components
+                    .values()
+                    .flatten()
+                    .filter(|container| container.is_running())
+                    .map(|container| container.id.clone())
+                    .collect();
+
+                let stats = engine
+                    .stats(&running)
+                    .await
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|stats| (stats.id.clone(), stats))
+                    .collect();
+
+                if tx.send(Message::Snapshot(status, stats)).is_err() {
+                    return;
+                }
+            }
+            Err(err) => {
+                let _ = tx.send(Message::Info(format!("status query failed: {}", err)));
+            }
+        }
+
+        let target = logs_target.lock().unwrap().clone();
+
+        if let Some(id) = target {
+            if let Ok(lines) = engine.logs(&id, LOG_LINES).await {
+                let _ = tx.send(Message::Logs(lines));
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = refresh.notified() => {}
+        }
+    }
+}
+
+impl App {
+    fn new(ws: &Workspace) -> App {
+        let services = ws
+            .get_executable_component_names()
+            .into_iter()
+            .map(|name| {
+                let services = ws.components[&name].get_services().unwrap_or_default();
+                (name, services)
+            })
+            .collect();
+
+        let mut app = App {
+            services,
+            rows: Vec::new(),
+            stats: HashMap::new(),
+            table_state: TableState::default(),
+            show_logs: false,
+            logs: Vec::new(),
+            message: String::new(),
+        };
+
+        app.apply(Message::Snapshot(WorkspaceStatus::default(), HashMap::new()));
+        app.table_state.select(Some(0));
+
+        app
+    }
+
+    fn apply(&mut self, message: Message) {
+        match message {
+            Message::Snapshot(status, stats) => {
+                self.rows = build_rows(&self.services, &status);
+                self.stats = stats;
+
+                let selected = self.table_state.selected().unwrap_or(0);
+                self.table_state
+                    .select(Some(selected.min(self.rows.len().saturating_sub(1))));
+            }
+            Message::Logs(lines) => self.logs = lines,
+            Message::Info(message) => self.message = message,
+        }
+    }
+
+    fn selected(&self) -> Option<&TopRow> {
+        self.table_state.selected().and_then(|i| self.rows.get(i))
+    }
+
+    fn select_next(&mut self) {
+        let selected = self.table_state.selected().unwrap_or(0);
+
+        if selected + 1 < self.rows.len() {
+            self.table_state.select(Some(selected + 1));
+            self.logs.clear();
+        }
+    }
+
+    fn select_previous(&mut self) {
+        let selected = self.table_state.selected().unwrap_or(0);
+
+        if selected > 0 {
+            self.table_state.select(Some(selected - 1));
+            self.logs.clear();
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame) {
+        let constraints = if self.show_logs {
+            vec![
+                Constraint::Percentage(50),
+                Constraint::Percentage(50),
+                Constraint::Length(1),
+            ]
+        } else {
+            vec![Constraint::Min(3), Constraint::Length(1)]
+        };
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(f.size());
+
+        let mut previous_component = "";
+        let rows: Vec<Row> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let name = if row.component == previous_component {
+                    ""
+                } else {
+                    row.component.as_str()
+                };
+                previous_component = &row.component;
+
+                let stats = row.container.as_ref().and_then(|c| self.stats.get(&c.id));
+                let (status, color) = match &row.container {
+                    Some(container) if container.is_running() => ("Running", Color::Green),
+                    Some(_) | None => ("Exited", Color::DarkGray),
+                };
+
+                Row::new(vec![
+                    name.to_string(),
+                    row.service.clone(),
+                    status.to_string(),
+                    row.container
+                        .as_ref()
+                        .and_then(|c| c.health())
+                        .unwrap_or("")
+                        .to_string(),
+                    stats.map(|s| s.cpu.clone()).unwrap_or_default(),
+                    stats.map(|s| s.memory.clone()).unwrap_or_default(),
+                    row.container
+                        .as_ref()
+                        .and_then(|c| c.uptime())
+                        .unwrap_or("")
+                        .to_string(),
+                ])
+                .style(Style::default().fg(color))
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(22),
+                Constraint::Percentage(14),
+                Constraint::Percentage(10),
+                Constraint::Percentage(12),
+                Constraint::Percentage(10),
+                Constraint::Percentage(18),
+                Constraint::Percentage(14),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "Name", "Service", "Status", "Health", "CPU", "Memory", "Uptime",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::default().borders(Borders::ALL).title(" uniform top "))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        f.render_stateful_widget(table, chunks[0], &mut self.table_state);
+
+        if self.show_logs {
+            let height = chunks[1].height.saturating_sub(2) as usize;
+            let skip = self.logs.len().saturating_sub(height);
+            let title = self
+                .selected()
+                .map(|row| format!(" logs: {} {} ", row.component, row.service))
+                .unwrap_or_default();
+
+            let logs = Paragraph::new(self.logs[skip..].join("\n"))
+                .block(Block::default().borders(Borders::ALL).title(title));
+
+            f.render_widget(logs, chunks[1]);
+        }
+
+        let help = format!(
+            "q quit  ↑↓ select  s start  x stop  r restart  l logs  e shell   {}",
+            self.message
+        );
+        f.render_widget(Paragraph::new(help), chunks[chunks.len() - 1]);
+    }
+}
+
+fn build_rows(services: &[(String, Vec<String>)], status: &WorkspaceStatus) -> Vec<TopRow> {
+    let mut rows = Vec::new();
+
+    for (component, declared) in services {
+        let services = status.services(component, declared);
+
+        if services.is_empty() {
+            rows.push(TopRow {
+                component: component.clone(),
+                service: String::new(),
+                container: None,
+            });
+        }
+
+        for (service, container) in services {
+            rows.push(TopRow {
+                component: component.clone(),
+                service,
+                container: container.cloned(),
+            });
+        }
+    }
+
+    rows
+}
+
+fn action_name(action: char) -> &'static str {
+    match action {
+        's' => "start",
+        'x' => "stop",
+        _ => "restart",
+    }
+}
+
+fn enter_tui() -> Result<Terminal<CrosstermBackend<Stdout>>, Box<dyn Error>> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+
+    Ok(Terminal::new(CrosstermBackend::new(io::stdout()))?)
+}
+
+fn leave_tui(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<(), Box<dyn Error>> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    Ok(())
+}
+
+fn resume_tui(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<(), Box<dyn Error>> {
+    enable_raw_mode()?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    terminal.clear()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_engine::FakeEngine;
+
+    #[test]
+    fn rows_for_every_service() {
+        let engine = FakeEngine::new();
+        engine.add_container("shop", "backend", "app", true);
+        engine.add_container("shop", "backend", "queue", false);
+
+        let containers = Runtime::new()
+            .unwrap()
+            .block_on(engine.list_containers("shop"))
+            .unwrap();
+        let status = WorkspaceStatus::from_containers(containers);
+
+        let services = vec![
+            (
+                "backend".to_string(),
+                vec!["app".to_string(), "worker".to_string()],
+            ),
+            ("database".to_string(), vec!["db".to_string()]),
+            ("docs".to_string(), vec![]),
+        ];
+        let rows = build_rows(&services, &status);
+
+        let summary: Vec<(&str, &str, Option<bool>)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.component.as_str(),
+                    row.service.as_str(),
+                    row.container.as_ref().map(|c| c.is_running()),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                ("backend", "app", Some(true)),
+                ("backend", "worker", None),
+                ("backend", "queue", Some(false)),
+                ("database", "db", None),
+                ("docs", "", None),
+            ]
+        );
+    }
+}
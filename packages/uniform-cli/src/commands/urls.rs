@@ -0,0 +1,63 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use prettytable::{format, Table};
+
+use crate::{
+    args::ExecuteTrait, core::GlobalOptions, proxy::collect_routes, settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct UrlsCommand {
+    /// Show the container each hostname is forwarded to
+    #[arg(long)]
+    upstream: bool,
+}
+
+impl ExecuteTrait for UrlsCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let routes = collect_routes(&ws)?;
+
+        if global_options.json {
+            println!("{}", serde_json::to_string_pretty(&routes)?);
+            return Ok(None);
+        }
+
+        if routes.is_empty() {
+            println!("{}", "No routed components".bright_yellow());
+            return Ok(None);
+        }
+
+        let mut table = Table::new();
+
+        if self.upstream {
+            table.set_titles(row!["Name", "URL", "Upstream"]);
+        } else {
+            table.set_titles(row!["Name", "URL"]);
+        }
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        for route in routes {
+            if self.upstream {
+                table.add_row(row![route.component, route.url, route.upstream]);
+            } else {
+                table.add_row(row![route.component, route.url]);
+            }
+        }
+
+        table.printstd();
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,85 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::GlobalOptions,
+    settings::Settings,
+    watch::{watch, WatchTarget},
+    workspace::create_workspace,
+};
+
+/// Runs the watch actions of components when their files change
+#[derive(Debug, Args)]
+pub struct WatchCommand {
+    /// Components to watch, all with a watch section by default
+    components: Vec<String>,
+}
+
+impl ExecuteTrait for WatchCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let names = if self.components.is_empty() {
+            ws.get_executable_component_names()
+        } else {
+            self.components
+                .iter()
+                .map(|name| ws.resolve_name(name))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+
+        let mut targets = Vec::new();
+
+        for name in &names {
+            let component = ws.components.get(name).unwrap();
+
+            let target = match WatchTarget::new(&ws, component)? {
+                Some(target) => target,
+                None if self.components.is_empty() => continue,
+                None => {
+                    return Err(Box::new(CliError::new(format!(
+                        "Component \"{}\" has no watch section",
+                        name
+                    ))))
+                }
+            };
+
+            if !target.root.is_dir() {
+                println!(
+                    "{} {} does not exist, not watching \"{}\"",
+                    "Warning:".yellow(),
+                    target.root.display(),
+                    name
+                );
+                continue;
+            }
+
+            println!(
+                "Watching {} {} -> {}",
+                name.bright_green(),
+                target.config.paths.join(", "),
+                target.action_label()
+            );
+            targets.push(target);
+        }
+
+        if targets.is_empty() {
+            return Err(Box::new(CliError::new(
+                "Nothing to watch, add a watch section to a component",
+            )));
+        }
+
+        watch(&ws, &targets, global_options)?;
+
+        Ok(None)
+    }
+}
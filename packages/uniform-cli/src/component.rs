@@ -1,15 +1,23 @@
-use crate::component_config::{get_deps, resolve_deps, ComponentConfig};
+use crate::args::CliError;
+use crate::component_config::{
+    get_deps, resolve_deps, ComponentConfig, CustomCommand, Hook, HookEvent,
+};
 use crate::core::{
-    exec_shell_interactive, exec_shell_to_string, subst_vars, GlobalOptions, Mode, ShellError,
+    docker_program, exec_shell_interactive, exec_shell_piped, exec_shell_to_string, path_to_unix,
+    shell_quote, subst_known_vars, subst_vars, GlobalOptions, Mode, ShellError,
 };
-use crate::workspace::{self, Workspace};
+use crate::engine::{COMPONENT_LABEL, WORKSPACE_LABEL};
+use crate::plan::Plan;
+use crate::state::WorkspaceState;
+use crate::status::WorkspaceStatus;
+use crate::workspace::{self, Target, Workspace};
 use atty;
 use colored::Colorize;
 use indexmap::IndexMap;
 use std::collections::HashMap;
 use std::error::Error;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::process::{Command, ExitStatus};
 
@@ -24,34 +32,36 @@ pub struct Component {
 
 pub type ErrCode = i32;
 
+pub const DEFAULT_HOSTNAME: &str = "${APP_NAME}.${BASE_DOMAIN:localhost}";
+
 impl Component {
-    pub fn get_container_id(
+    /// Component this one runs inside of, if it is hosted
+    pub fn get_host<'a>(
         &self,
-        workspace: &Workspace,
-        options: &GlobalOptions,
-    ) -> Result<String, Box<dyn Error + 'static>> {
-        let out = self.exec_compose(
-            workspace,
-            &[
-                "ps".to_string(),
-                "--status=running".to_string(),
-                "-q".to_string(),
-            ],
-            options,
-            false,
-        )?;
+        workspace: &'a Workspace,
+    ) -> Result<Option<&'a Component>, Box<dyn Error>> {
+        let host_name = match &self.config.hosted_in {
+            Some(host_name) => host_name,
+            None => return Ok(None),
+        };
 
-        return Ok(out);
-    }
+        let host = workspace.components.get(host_name).ok_or_else(|| {
+            format!(
+                "Component \"{}\" is hosted in unknown component \"{}\"",
+                self.name, host_name
+            )
+        })?;
 
-    pub fn is_running(
-        &self,
-        workspace: &Workspace,
-        options: &GlobalOptions,
-    ) -> Result<bool, Box<dyn Error>> {
-        let out = self.get_container_id(workspace, options)?;
+        Ok(Some(host))
+    }
 
-        Ok(!out.is_empty())
+    /// Compose service `exec` targets unless another one is requested
+    pub fn get_service(&self) -> String {
+        self.config
+            .service
+            .clone()
+            .or_else(|| self.template.as_ref()?.service.clone())
+            .unwrap_or_else(|| "app".to_string())
     }
 
     pub fn init(&self, workspace: &Workspace) -> Result<Component, Box<dyn Error>> {
@@ -72,6 +82,19 @@ impl Component {
         let svc_path = subst_vars(&self.config.path.as_ref().unwrap().clone(), &ctx);
         ctx.insert("SVC_PATH".to_string(), svc_path);
 
+        // Compose files and variables pick the hostname up as ${APP_HOSTNAME}
+        let hostname = self
+            .config
+            .hostname
+            .clone()
+            .or_else(|| {
+                let extends = self.config.extends.as_ref()?;
+                workspace.config.as_ref()?.components.get(extends)?.hostname.clone()
+            })
+            .unwrap_or_else(|| DEFAULT_HOSTNAME.to_string());
+        let hostname = subst_vars(&hostname, &ctx);
+        ctx.insert("APP_HOSTNAME".to_string(), hostname);
+
         if let Some(extends) = &self.config.extends {
             let mut template = workspace
                 .config
@@ -151,6 +174,229 @@ impl Component {
         Ok(result)
     }
 
+    /// Builds the full engine command line for a compose subcommand
+    pub fn compose_command(&self, compose_command: &[String]) -> Vec<String> {
+        let compose_file = self.context.as_ref().unwrap().get("COMPOSE_FILE").unwrap();
+
+        let mut command = vec![
+            shell_quote(&docker_program()),
+            "compose".to_string(),
+            "-f".to_string(),
+            compose_file.clone(),
+            "-f".to_string(),
+            self.labels_file(),
+        ];
+        command.extend_from_slice(compose_command);
+
+        command
+    }
+
+    /// Compose override that labels every service with workspace and component
+    pub fn labels_file(&self) -> String {
+        let home_path = self.context.as_ref().unwrap().get("HOME_PATH").unwrap();
+
+        format!("{}/compose/{}.labels.json", home_path, self.name)
+    }
+
+    /// Custom commands of the component, those of its template first
+    pub fn get_commands(&self) -> IndexMap<String, CustomCommand> {
+        let mut commands = IndexMap::new();
+
+        for config in self.template.iter().chain(std::iter::once(&self.config)) {
+            if let Some(own) = &config.commands {
+                commands.extend(own.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+
+        commands
+    }
+
+    /// Hostname the component is routed on. Only components declaring a
+    /// `hostname`, or extending a template that does, are routed, an empty
+    /// one disables routing.
+    pub fn get_hostname(&self) -> Option<String> {
+        self.config
+            .hostname
+            .as_ref()
+            .or_else(|| self.template.as_ref()?.hostname.as_ref())?;
+
+        let hostname = self.context.as_ref()?.get("APP_HOSTNAME")?;
+
+        if hostname.is_empty() {
+            return None;
+        }
+
+        Some(hostname.clone())
+    }
+
+    /// Name compose gives to the container of the main service
+    pub fn get_container_name(&self) -> String {
+        let project = self
+            .context
+            .as_ref()
+            .unwrap()
+            .get("COMPOSE_PROJECT_NAME")
+            .unwrap();
+
+        format!("{}-{}-1", project, self.get_service())
+    }
+
+    /// Port the component serves HTTP on inside its container, `APP_PORT` or 80
+    pub fn get_app_port(&self) -> Result<u16, Box<dyn Error>> {
+        let port = match self.context.as_ref().unwrap().get("APP_PORT") {
+            Some(port) => port,
+            None => return Ok(80),
+        };
+
+        port.parse().map_err(|_| {
+            format!("Component \"{}\" has invalid APP_PORT \"{}\"", self.name, port).into()
+        })
+    }
+
+    /// Component and service whose container serves this component's
+    /// requests, hosted components are served by their host
+    pub fn get_upstream_target(&self, workspace: &Workspace) -> Result<Target, Box<dyn Error>> {
+        let component = self.get_host(workspace)?.unwrap_or(self);
+
+        Ok(Target {
+            component: component.name.clone(),
+            service: Some(component.get_service()),
+        })
+    }
+
+    /// `container:port` the proxy forwards requests for this component to
+    pub fn get_upstream(&self, workspace: &Workspace) -> Result<String, Box<dyn Error>> {
+        let container = match self.get_host(workspace)? {
+            Some(host) => host.get_container_name(),
+            None => self.get_container_name(),
+        };
+
+        Ok(format!("{}:{}", container, self.get_app_port()?))
+    }
+
+    /// Whether the component or the template it extends has the tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        [Some(&self.config), self.template.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter_map(|config| config.tags.as_ref())
+            .any(|tags| tags.iter().any(|t| t == tag))
+    }
+
+    /// Working dir inside the container for a command run from `host_cwd`.
+    /// A host subdirectory of the component path maps to the same
+    /// subdirectory under `exec_path`.
+    pub fn get_exec_dir(&self, host_cwd: &str) -> Option<String> {
+        let exec_path = self
+            .config
+            .exec_path
+            .as_ref()
+            .or_else(|| self.template.as_ref()?.exec_path.as_ref())?;
+        let ctx = self.context.as_ref().unwrap();
+        let exec_path = subst_vars(exec_path, ctx);
+
+        let svc_path = path_to_unix(ctx.get("SVC_PATH")?);
+        let host_cwd = path_to_unix(host_cwd);
+
+        let relative = Path::new(&host_cwd)
+            .strip_prefix(&svc_path)
+            .ok()
+            .map(|relative| path_to_unix(relative.to_string_lossy()))
+            .unwrap_or_default();
+
+        if relative.is_empty() {
+            return Some(exec_path);
+        }
+
+        Some(format!("{}/{}", exec_path.trim_end_matches('/'), relative))
+    }
+
+    /// Variables declared by the component and its template, resolved
+    pub fn get_variables(&self) -> IndexMap<String, String> {
+        let ctx = self.context.as_ref().unwrap();
+        let mut keys: Vec<&String> = Vec::new();
+
+        if let Some(vars) = self.template.as_ref().and_then(|t| t.variables.as_ref()) {
+            keys.extend(vars.keys());
+        }
+
+        if let Some(vars) = &self.config.variables {
+            keys.extend(vars.keys());
+        }
+
+        let mut variables = IndexMap::new();
+
+        for key in keys {
+            if let Some(value) = ctx.get(key) {
+                variables.insert(key.clone(), value.clone());
+            }
+        }
+
+        variables
+    }
+
+    pub fn get_services(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        let compose_file = self.context.as_ref().unwrap().get("COMPOSE_FILE").unwrap();
+        let content = std::fs::read_to_string(compose_file)?;
+        let compose: serde_yaml::Value = serde_yaml::from_str(&content)?;
+
+        let services = compose
+            .get("services")
+            .and_then(|services| services.as_mapping())
+            .map(|services| {
+                services
+                    .keys()
+                    .filter_map(|k| k.as_str().map(|k| k.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(services)
+    }
+
+    pub fn write_labels_file(&self) -> Result<(), Box<dyn Error>> {
+        let ctx = self.context.as_ref().unwrap();
+        // A missing compose file is reported by compose itself
+        let services = self.get_services().unwrap_or_default();
+
+        let mut labels = serde_json::Map::new();
+        labels.insert(
+            WORKSPACE_LABEL.to_string(),
+            ctx.get("WORKSPACE_NAME").cloned().unwrap_or_default().into(),
+        );
+        labels.insert(COMPONENT_LABEL.to_string(), self.name.clone().into());
+
+        let mut overrides = serde_json::Map::new();
+        for service in services {
+            overrides.insert(service, serde_json::json!({ "labels": labels }));
+        }
+
+        let content = serde_json::to_string_pretty(&serde_json::json!({ "services": overrides }))?;
+        let path = PathBuf::from(self.labels_file());
+
+        if std::fs::read_to_string(&path).ok().as_deref() == Some(content.as_str()) {
+            return Ok(());
+        }
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(&path, content)?;
+
+        Ok(())
+    }
+
+    pub fn compose_env(&self) -> HashMap<String, String> {
+        let mut env = HashMap::new();
+
+        for (k, v) in self.context.as_ref().unwrap().iter() {
+            env.insert(k.clone(), v.clone());
+        }
+
+        env
+    }
+
     pub fn exec_compose(
         &self,
         workspace: &Workspace,
@@ -163,17 +409,7 @@ impl Component {
         //     self.name, self.context
         // );
 
-        let compose_file = self.context.as_ref().unwrap().get("COMPOSE_FILE").unwrap();
-
-        let mut command = vec![
-            "docker".to_string(),
-            "compose".to_string(),
-            "-f".to_string(),
-            compose_file.clone(),
-        ];
-        command.extend_from_slice(compose_command);
-
-        let command_str = command.join(" ");
+        let command_str = self.compose_command(compose_command).join(" ");
 
         if options.debug {
             println!(
@@ -187,11 +423,9 @@ impl Component {
             return Ok("".to_string());
         }
 
-        let mut env = HashMap::new();
+        self.write_labels_file()?;
 
-        for (k, v) in self.context.as_ref().unwrap().iter() {
-            env.insert(k.clone(), v.clone());
-        }
+        let env = self.compose_env();
 
         if options.debug {
             println!(">> ENV: {:?}", env);
@@ -219,53 +453,48 @@ impl Component {
         workspace: &Workspace,
         options: &GlobalOptions,
     ) -> Result<Option<String>, Box<dyn Error>> {
-        let running = self.is_running(workspace, options)?;
-
-        if !running || options.force {
-            let deps = get_deps(
-                &self.config,
-                options.mode.as_ref().unwrap_or(&Mode::Default),
-            );
-            let comps: Vec<Component> = resolve_deps(&workspace, &deps);
+        self.start_service(workspace, None, options)
+    }
 
-            println!(
-                "deps: {}",
-                comps
-                    .iter()
-                    .map(|e| e.name.clone())
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            );
+    /// Starts the whole component or just one of its compose services
+    pub fn start_service(
+        &self,
+        workspace: &Workspace,
+        service: Option<&str>,
+        options: &GlobalOptions,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        let target = Target {
+            component: self.name.clone(),
+            service: service.map(|s| s.to_string()),
+        };
+        let plan = Plan::start(workspace, &[target], options)?;
 
-            for mut comp in comps {
-                let running = self.is_running(workspace, options)?;
+        let deps = plan
+            .steps
+            .iter()
+            .filter(|step| step.component != self.name)
+            .map(|step| step.component.clone())
+            .collect::<Vec<_>>();
 
-                if !running || options.force {
-                    comp.exec_compose(
-                        workspace,
-                        &["up".to_string(), "-d".to_string()],
-                        options,
-                        true,
-                    )?;
-                }
-            }
+        if !deps.is_empty() {
+            println!("deps: {}", deps.join(", "));
         }
 
-        if running {
+        plan.execute(workspace, options)?;
+
+        let requested_skipped = plan
+            .steps
+            .iter()
+            .any(|step| step.component == self.name && step.skip);
+
+        if requested_skipped {
             return Ok(Some(format!(
                 "🚀 Component \"{}\" is already running",
                 self.name
             )));
         }
 
-        let result = self.exec_compose(
-            workspace,
-            &["up".to_string(), "-d".to_string()],
-            options,
-            true,
-        )?;
-
-        return Ok(Some(result));
+        Ok(Some("".to_string()))
     }
 
     pub fn stop(
@@ -273,20 +502,92 @@ impl Component {
         workspace: &Workspace,
         options: &GlobalOptions,
     ) -> Result<Option<String>, Box<dyn Error>> {
-        let running = self.is_running(workspace, options)?;
+        self.stop_service(workspace, None, options)
+    }
 
-        if !running {
+    /// Stops the whole component or just one of its compose services
+    pub fn stop_service(
+        &self,
+        workspace: &Workspace,
+        service: Option<&str>,
+        options: &GlobalOptions,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        if let Some(host_name) = &self.config.hosted_in {
+            return Ok(Some(format!(
+                "🏠 Component \"{}\" runs inside \"{}\", stop it instead",
+                self.name, host_name
+            )));
+        }
+
+        let status = WorkspaceStatus::load(workspace, options)?;
+
+        if !status.is_service_running(&self.name, service) {
             return Ok(Some(format!(
                 "📴 Component \"{}\" is already stopped",
                 self.name
             )));
         }
 
-        let result = self.exec_compose(workspace, &["stop".to_string()], options, true)?;
+        self.run_hooks(workspace, HookEvent::BeforeStop, options)?;
+
+        let mut command = vec!["stop".to_string()];
+        command.extend(service.map(|s| s.to_string()));
+
+        let result = self.exec_compose(workspace, &command, options, true)?;
+
+        self.run_hooks(workspace, HookEvent::AfterStop, options)?;
+
+        // Other services of the component keep running
+        if service.is_none() {
+            self.forget_state(workspace, options)?;
+        }
 
         Ok(Some(result))
     }
 
+    /// Removes the component's containers, with `volumes` also the volumes
+    /// its compose file declares
+    pub fn down(
+        &self,
+        workspace: &Workspace,
+        volumes: bool,
+        options: &GlobalOptions,
+    ) -> Result<Option<String>, Box<dyn Error>> {
+        if let Some(host_name) = &self.config.hosted_in {
+            return Ok(Some(format!(
+                "🏠 Component \"{}\" runs inside \"{}\", take it down instead",
+                self.name, host_name
+            )));
+        }
+
+        let mut command = vec!["down".to_string()];
+        if volumes {
+            command.push("--volumes".to_string());
+        }
+
+        self.exec_compose(workspace, &command, options, true)?;
+        self.forget_state(workspace, options)?;
+
+        self.run_hooks(workspace, HookEvent::AfterDown, options)?;
+
+        Ok(None)
+    }
+
+    /// Drops the component from the workspace state once it is no longer running
+    fn forget_state(
+        &self,
+        workspace: &Workspace,
+        options: &GlobalOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        if options.dry_run {
+            return Ok(());
+        }
+
+        let mut state = WorkspaceState::load(workspace)?;
+        state.remove(&self.name);
+        state.save(workspace)
+    }
+
     pub fn exec(
         &self,
         workspace: &Workspace,
@@ -323,14 +624,18 @@ impl Component {
         workspace: &Workspace,
         options: &GlobalOptions,
     ) -> Result<(), Box<dyn Error>> {
-        let running = self.is_running(workspace, options)?;
+        let status = WorkspaceStatus::load(workspace, options)?;
 
-        if !running {
+        if !status.is_running(&self.name) {
             return Ok(());
         }
 
         self.exec_compose(workspace, &["down".to_string()], options, true)?;
 
+        self.forget_state(workspace, options)?;
+
+        self.run_hooks(workspace, HookEvent::AfterDown, options)?;
+
         Ok(())
     }
 
@@ -369,45 +674,298 @@ impl Component {
         workspace: &mut Workspace,
         options: &GlobalOptions,
     ) -> Result<(), Box<dyn Error>> {
+        if let Some(host_name) = &self.config.hosted_in {
+            // Starts the host along with the dependencies of this component
+            self.start(workspace, options)?;
+
+            let mut host = workspace
+                .components
+                .get(host_name)
+                .ok_or_else(|| {
+                    format!(
+                        "Component \"{}\" is hosted in unknown component \"{}\"",
+                        self.name, host_name
+                    )
+                })?
+                .clone();
+
+            // Hosted components run in the host container with their own
+            // working dir and variables
+            let mut host_options = options.clone();
+            host_options.working_dir = options
+                .working_dir
+                .clone()
+                .or_else(|| self.get_exec_dir(&workspace.cwd));
+            host_options.env = Some(self.get_variables());
+
+            return host.exec_custom_command(workspace, &host_options);
+        }
+
         self.start(workspace, options)?;
 
         let mut command = vec!["exec".to_string()];
 
-        if let Some(working_dir) = &options.working_dir {
+        let working_dir = options
+            .working_dir
+            .clone()
+            .or_else(|| self.get_exec_dir(&workspace.cwd));
+
+        if let Some(working_dir) = working_dir {
             command.push("-w".to_string());
-            command.push(working_dir.clone());
+            command.push(shell_quote(&working_dir));
         }
 
-        if let Some(uid) = options.uid {
-            command.push("-u".to_string());
-            command.push(uid.to_string());
-        } else {
-            let user_id = self
-                .context
-                .as_ref()
-                .unwrap()
-                .get("USER_ID")
-                .ok_or_else(|| "variable \"USER_ID\" is not set".to_string())?;
-            let group_id = self
-                .context
-                .as_ref()
-                .unwrap()
-                .get("GROUP_ID")
-                .ok_or_else(|| "variable \"GROUP_ID\" is not set".to_string())?;
+        command.extend(self.user_args(options)?);
 
-            command.push("-u".to_string());
-            command.push(format!("{}:{}", user_id, group_id));
+        if let Some(env) = &options.env {
+            for (k, v) in env.iter() {
+                command.push("-e".to_string());
+                command.push(shell_quote(&format!("{}={}", k, v)));
+            }
         }
 
         if options.no_tty || !atty::is(atty::Stream::Stdout) {
             command.push("-T".to_string());
         }
 
-        command.push("app".to_string());
+        command.push(options.service.clone().unwrap_or_else(|| self.get_service()));
         command.extend_from_slice(options.cmd.as_ref().unwrap().as_slice());
 
         self.exec_compose(workspace, &command, options, true)?;
 
         Ok(())
     }
+
+    /// Runs `options.cmd` in a throwaway container of the component that is
+    /// removed afterwards. A failing command is reported with its exit code.
+    pub fn run_once(
+        &self,
+        workspace: &Workspace,
+        options: &GlobalOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(host_name) = &self.config.hosted_in {
+            let host = workspace.components.get(host_name).ok_or_else(|| {
+                format!(
+                    "Component \"{}\" is hosted in unknown component \"{}\"",
+                    self.name, host_name
+                )
+            })?;
+
+            // Same as exec, the host image with the hosted working dir and variables
+            let mut host_options = options.clone();
+            host_options.working_dir = options
+                .working_dir
+                .clone()
+                .or_else(|| self.get_exec_dir(&workspace.cwd));
+            host_options.env = Some(self.get_variables());
+
+            return host.run_once(workspace, &host_options);
+        }
+
+        let mut command = vec!["run".to_string(), "--rm".to_string()];
+
+        let working_dir = options
+            .working_dir
+            .clone()
+            .or_else(|| self.get_exec_dir(&workspace.cwd));
+
+        if let Some(working_dir) = working_dir {
+            command.push("-w".to_string());
+            command.push(shell_quote(&working_dir));
+        }
+
+        command.extend(self.user_args(options)?);
+
+        if let Some(env) = &options.env {
+            for (k, v) in env.iter() {
+                command.push("-e".to_string());
+                command.push(shell_quote(&format!("{}={}", k, v)));
+            }
+        }
+
+        if options.no_tty || !atty::is(atty::Stream::Stdout) {
+            command.push("-T".to_string());
+        }
+
+        command.push(options.service.clone().unwrap_or_else(|| self.get_service()));
+        // The line goes through the host shell, each argument has to stay one word
+        command.extend(options.cmd.as_ref().unwrap().iter().map(|arg| shell_quote(arg)));
+
+        let command_str = self.compose_command(&command).join(" ");
+
+        if options.debug || options.dry_run {
+            println!(">> {}", command_str);
+        }
+
+        if options.dry_run {
+            return Ok(());
+        }
+
+        self.write_labels_file()?;
+
+        // Unlike exec_compose, keeps the exit code of the command
+        exec_shell_piped(&command_str, &workspace.cwd, &self.compose_env(), None, None)
+    }
+
+    /// Runs the hooks of an event, those of the template first. A failing
+    /// `before_*` hook is an error, failing later ones are only reported and
+    /// the remaining hooks still run.
+    pub fn run_hooks(
+        &self,
+        workspace: &Workspace,
+        event: HookEvent,
+        options: &GlobalOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        let hooks: Vec<&Hook> = self
+            .template
+            .iter()
+            .chain(std::iter::once(&self.config))
+            .filter_map(|config| event.hooks(config))
+            .flatten()
+            .collect();
+
+        for hook in hooks {
+            let result = self.run_hook(workspace, event, hook, options);
+
+            match result {
+                Err(err) if event.is_before() => {
+                    return Err(Box::new(CliError::new(format!(
+                        "{} hook of \"{}\" failed: {}",
+                        event.name(),
+                        self.name,
+                        err
+                    ))))
+                }
+                Err(err) => {
+                    println!(
+                        "{} {} hook of \"{}\" failed: {}",
+                        "Warning:".yellow(),
+                        event.name(),
+                        self.name,
+                        err
+                    );
+                }
+                Ok(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn run_hook(
+        &self,
+        workspace: &Workspace,
+        event: HookEvent,
+        hook: &Hook,
+        options: &GlobalOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        let ctx = self.context.as_ref().unwrap();
+
+        let (command, env, cwd) = match hook {
+            Hook::Host(command) => {
+                let command = subst_known_vars(command, ctx);
+                println!("{} {} $ {}", self.name.bright_green(), event.name(), command);
+
+                // Host hooks run in the component directory when it exists
+                let cwd = ctx
+                    .get("SVC_PATH")
+                    .filter(|path| Path::new(path).is_dir())
+                    .cloned()
+                    .unwrap_or_else(|| workspace.cwd.clone());
+
+                (command, self.compose_env(), cwd)
+            }
+            Hook::Exec { exec, service } => {
+                if !event.is_running() {
+                    return Err(Box::new(CliError::new(format!(
+                        "exec hooks can't run in {}, the containers are not up",
+                        event.name()
+                    ))));
+                }
+
+                let exec = subst_known_vars(exec, ctx);
+                println!("{} {} > {}", self.name.bright_green(), event.name(), exec);
+
+                let service = service.as_ref().map(|service| subst_vars(service, ctx));
+                let (command, env) =
+                    self.hook_exec_command(workspace, &exec, service, options)?;
+
+                (command, env, workspace.cwd.clone())
+            }
+        };
+
+        if options.debug || options.dry_run {
+            println!(">> {}", command);
+        }
+
+        if options.dry_run {
+            return Ok(());
+        }
+
+        exec_shell_piped(&command, &cwd, &env, None, None)
+    }
+
+    /// Compose exec of a hook or watch command, in the host container for hosted components
+    pub fn hook_exec_command(
+        &self,
+        workspace: &Workspace,
+        command: &str,
+        service: Option<String>,
+        options: &GlobalOptions,
+    ) -> Result<(String, HashMap<String, String>), Box<dyn Error>> {
+        let runner = match &self.config.hosted_in {
+            Some(host_name) => workspace.components.get(host_name).ok_or_else(|| {
+                format!(
+                    "Component \"{}\" is hosted in unknown component \"{}\"",
+                    self.name, host_name
+                )
+            })?,
+            None => self,
+        };
+
+        let mut args = vec!["exec".to_string(), "-T".to_string()];
+        args.extend(self.user_args(options)?);
+
+        let svc_path = self.context.as_ref().unwrap().get("SVC_PATH").cloned();
+        if let Some(working_dir) = svc_path.and_then(|path| self.get_exec_dir(&path)) {
+            args.push("-w".to_string());
+            args.push(working_dir);
+        }
+
+        if self.config.hosted_in.is_some() {
+            for (k, v) in self.get_variables() {
+                args.push("-e".to_string());
+                args.push(shell_quote(&format!("{}={}", k, v)));
+            }
+        }
+
+        args.push(service.unwrap_or_else(|| runner.get_service()));
+        args.push("sh".to_string());
+        args.push("-c".to_string());
+        args.push(shell_quote(command));
+
+        Ok((runner.compose_command(&args).join(" "), runner.compose_env()))
+    }
+
+    /// `-u` argument for exec and run
+    fn user_args(&self, options: &GlobalOptions) -> Result<Vec<String>, Box<dyn Error>> {
+        Ok(vec!["-u".to_string(), self.get_user(options)?])
+    }
+
+    /// User commands run as in the containers, `--uid` or USER_ID:GROUP_ID
+    pub fn get_user(&self, options: &GlobalOptions) -> Result<String, Box<dyn Error>> {
+        if let Some(uid) = options.uid {
+            return Ok(uid.to_string());
+        }
+
+        let ctx = self.context.as_ref().unwrap();
+        let user_id = ctx
+            .get("USER_ID")
+            .ok_or_else(|| "variable \"USER_ID\" is not set".to_string())?;
+        let group_id = ctx
+            .get("GROUP_ID")
+            .ok_or_else(|| "variable \"GROUP_ID\" is not set".to_string())?;
+
+        Ok(format!("{}:{}", user_id, group_id))
+    }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use colored::Colorize;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -17,7 +18,11 @@ pub struct ComponentConfig {
     pub exec_path: Option<String>,
     pub extends: Option<String>,
     pub hosted_in: Option<String>,
+    /// Hostname the component is routed on, components without one in their
+    /// own or template config are not routed
     pub hostname: Option<String>,
+    /// Compose service commands are executed in, "app" by default
+    pub service: Option<String>,
 
     #[serde(rename = "isTemplate")]
     pub is_template: Option<bool>,
@@ -27,6 +32,138 @@ pub struct ComponentConfig {
     pub repository: Option<String>,
     pub tags: Option<Vec<String>>,
     pub after_clone_hook: Option<String>,
+    pub backup: Option<BackupConfig>,
+    /// Named commands run via exec, e.g. `uniform backend migrate`
+    pub commands: Option<IndexMap<String, CustomCommand>>,
+    pub before_start: Option<Vec<Hook>>,
+    pub after_start: Option<Vec<Hook>>,
+    pub before_stop: Option<Vec<Hook>>,
+    pub after_stop: Option<Vec<Hook>>,
+    pub after_down: Option<Vec<Hook>>,
+    pub watch: Option<WatchConfig>,
+}
+
+/// Host command run with the component context, or one run in its container
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Hook {
+    Host(String),
+    Exec {
+        exec: String,
+        /// Compose service to exec in, the component service by default
+        service: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HookEvent {
+    BeforeStart,
+    AfterStart,
+    BeforeStop,
+    AfterStop,
+    AfterDown,
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HookEvent::BeforeStart => "before_start",
+            HookEvent::AfterStart => "after_start",
+            HookEvent::BeforeStop => "before_stop",
+            HookEvent::AfterStop => "after_stop",
+            HookEvent::AfterDown => "after_down",
+        }
+    }
+
+    /// Failing hooks of these events abort the action
+    pub fn is_before(&self) -> bool {
+        matches!(self, HookEvent::BeforeStart | HookEvent::BeforeStop)
+    }
+
+    /// Whether the containers of the component are up when the hook runs
+    pub fn is_running(&self) -> bool {
+        matches!(self, HookEvent::AfterStart | HookEvent::BeforeStop)
+    }
+
+    pub fn hooks<'a>(&self, config: &'a ComponentConfig) -> Option<&'a Vec<Hook>> {
+        match self {
+            HookEvent::BeforeStart => config.before_start.as_ref(),
+            HookEvent::AfterStart => config.after_start.as_ref(),
+            HookEvent::BeforeStop => config.before_stop.as_ref(),
+            HookEvent::AfterStop => config.after_stop.as_ref(),
+            HookEvent::AfterDown => config.after_down.as_ref(),
+        }
+    }
+}
+
+/// Command line run in the component container, or one with a description
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum CustomCommand {
+    Line(String),
+    Full {
+        command: String,
+        description: Option<String>,
+    },
+}
+
+impl CustomCommand {
+    pub fn command(&self) -> &str {
+        match self {
+            CustomCommand::Line(command) => command,
+            CustomCommand::Full { command, .. } => command,
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            CustomCommand::Line(_) => None,
+            CustomCommand::Full { description, .. } => description.as_deref(),
+        }
+    }
+}
+
+/// How `uniform backup` dumps and restores the data of a component
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BackupConfig {
+    /// Built-in commands: "postgres", "mysql" or "volume"
+    pub preset: Option<String>,
+    /// Command run via exec that writes the dump to stdout. Component
+    /// variables are substituted, any other `${...}` is left to the shell.
+    pub dump: Option<String>,
+    /// Command run via exec that reads a dump from stdin, substituted like `dump`
+    pub restore: Option<String>,
+    /// Compose service to exec in, the component service by default
+    pub service: Option<String>,
+    /// Named volume the "volume" preset archives
+    pub volume: Option<String>,
+}
+
+/// Files `uniform watch` follows and what it does when they change
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WatchConfig {
+    /// Globs relative to the component path
+    pub paths: Vec<String>,
+    /// Globs of changed files that don't trigger the action
+    pub ignore: Option<Vec<String>>,
+    /// Milliseconds to wait for more changes before acting, 500 by default
+    pub debounce: Option<u64>,
+    pub action: WatchAction,
+}
+
+/// "restart", a command run via exec or a workspace script
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum WatchAction {
+    Named(String),
+    Exec {
+        exec: String,
+        /// Compose service to exec in, the component service by default
+        service: Option<String>,
+    },
+    Script {
+        script: String,
+    },
 }
 
 pub fn merge_component_configs(cc: &ComponentConfig, cc2: &ComponentConfig) -> ComponentConfig {
@@ -64,6 +201,14 @@ pub fn merge_component_configs(cc: &ComponentConfig, cc2: &ComponentConfig) -> C
         result.exec_path = cc2.exec_path.clone();
     }
 
+    if cc2.hostname.is_some() {
+        result.hostname = cc2.hostname.clone();
+    }
+
+    if cc2.service.is_some() {
+        result.service = cc2.service.clone();
+    }
+
     if cc2.alias.is_some() {
         result.alias = cc2.alias.clone();
     }
@@ -72,10 +217,45 @@ pub fn merge_component_configs(cc: &ComponentConfig, cc2: &ComponentConfig) -> C
         result.repository = cc2.repository.clone();
     }
 
+    if cc2.backup.is_some() {
+        result.backup = cc2.backup.clone();
+    }
+
     if cc2.after_clone_hook.is_some() {
         result.after_clone_hook = cc2.after_clone_hook.clone();
     }
 
+    if cc2.before_start.is_some() {
+        result.before_start = cc2.before_start.clone();
+    }
+
+    if cc2.after_start.is_some() {
+        result.after_start = cc2.after_start.clone();
+    }
+
+    if cc2.before_stop.is_some() {
+        result.before_stop = cc2.before_stop.clone();
+    }
+
+    if cc2.after_stop.is_some() {
+        result.after_stop = cc2.after_stop.clone();
+    }
+
+    if cc2.after_down.is_some() {
+        result.after_down = cc2.after_down.clone();
+    }
+
+    if cc2.watch.is_some() {
+        result.watch = cc2.watch.clone();
+    }
+
+    if let Some(commands) = &cc2.commands {
+        result
+            .commands
+            .get_or_insert_with(IndexMap::new)
+            .extend(commands.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+
     if let Some(vars) = &cc2.variables {
         let mut result_variables = result.variables.as_mut().unwrap();
 
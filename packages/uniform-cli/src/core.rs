@@ -3,7 +3,7 @@ use lazy_static::lazy_static;
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{self, Display};
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::process::{Command, Stdio};
 use std::str::FromStr;
 
@@ -77,14 +77,18 @@ pub struct GlobalOptions {
     pub force: bool,
     pub mode: Option<Mode>,
     pub working_dir: Option<String>,
+    pub service: Option<String>,
+    /// Extra environment passed to `exec`
+    pub env: Option<IndexMap<String, String>>,
     pub uid: Option<u32>,
     pub tag: Option<String>,
     pub dry_run: bool,
     pub no_tty: bool,
+    pub json: bool,
 }
 
 lazy_static! {
-    static ref R: regex::Regex = regex::Regex::new(r"\$\{([A-Z0-9_]+(\:[^\}]+)?)\}").unwrap();
+    static ref R: regex::Regex = regex::Regex::new(r"\$\{([A-Z0-9_@]+(\:[^\}]+)?)\}").unwrap();
 }
 
 pub fn subst_vars(content: &str, variables: &IndexMap<String, String>) -> String {
@@ -111,6 +115,40 @@ pub fn subst_vars(content: &str, variables: &IndexMap<String, String>) -> String
     .to_string()
 }
 
+/// Like `subst_vars`, but leaves variables the context doesn't know alone, so
+/// shell syntax such as `${PGUSER:-postgres}` reaches the shell intact
+pub fn subst_known_vars(content: &str, variables: &IndexMap<String, String>) -> String {
+    R.replace_all(content, |caps: &regex::Captures| {
+        let name = caps[1].split(':').next().unwrap();
+
+        if variables.contains_key(name) {
+            subst_vars(&caps[0], variables)
+        } else {
+            caps[0].to_string()
+        }
+    })
+    .to_string()
+}
+
+/// Names that become file names, like sessions and backups, must not
+/// contain path separators or start with a dot
+pub fn validate_name(kind: &str, name: &str) -> Result<(), Box<dyn Error>> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c))
+        && !name.starts_with('.');
+
+    if !valid {
+        return Err(Box::new(crate::args::CliError::new(format!(
+            "Invalid {} name \"{}\", use letters, digits, dots, dashes and underscores",
+            kind, name
+        ))));
+    }
+
+    Ok(())
+}
+
 pub fn generate_hook_script(scripts: &[String], uniform_binary: &str) -> String {
     let mut result = vec![
         "#!/bin/bash".to_string(),
@@ -128,6 +166,23 @@ pub fn generate_hook_script(scripts: &[String], uniform_binary: &str) -> String
     result.join("\n")
 }
 
+/// Quotes an argument for the shell commands are run through
+pub fn shell_quote(arg: &str) -> String {
+    let safe = arg
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-=/.,:@%+".contains(c));
+
+    if safe && !arg.is_empty() {
+        return arg.to_string();
+    }
+
+    if cfg!(target_family = "windows") {
+        return format!("\"{}\"", arg.replace('"', "\\\""));
+    }
+
+    format!("'{}'", arg.replace('\'', "'\\''"))
+}
+
 pub fn wsl_path<P: AsRef<str>>(path: P) -> String {
     let str_path = path.as_ref().to_string();
 
@@ -178,6 +233,22 @@ const SHELL: &str = "sh";
 #[cfg(target_family = "wasm")]
 const FLAG: &str = "-c";
 
+#[cfg(test)]
+thread_local! {
+    /// Docker CLI of the current test thread, so tests can run a fake one
+    pub static TEST_DOCKER: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Docker CLI uniform runs
+pub fn docker_program() -> String {
+    #[cfg(test)]
+    if let Some(docker) = TEST_DOCKER.with(|docker| docker.borrow().clone()) {
+        return docker;
+    }
+
+    "docker".to_string()
+}
+
 #[cfg(target_family = "windows")]
 const TRANSFORM_PATHS: bool = true;
 #[cfg(target_family = "unix")]
@@ -275,3 +346,115 @@ pub fn exec_shell_interactive(
 
     Ok(())
 }
+
+/// Runs a shell command with its output streamed into `output`, or its input
+/// read from `input`, e.g. to dump a database into a file
+pub fn exec_shell_piped(
+    cmd: &str,
+    cwd: &str,
+    env: &HashMap<String, String>,
+    input: Option<&mut dyn Read>,
+    output: Option<&mut dyn Write>,
+) -> Result<(), Box<dyn Error>> {
+    let mut command = Command::new(SHELL);
+    command.arg(FLAG).arg(cmd).current_dir(cwd);
+    command.envs(env_transform_paths(env));
+
+    command.stdin(if input.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::inherit()
+    });
+    command.stdout(if output.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::inherit()
+    });
+    command.stderr(Stdio::inherit());
+
+    let mut child = command.spawn()?;
+
+    if let Some(input) = input {
+        let mut stdin = child.stdin.take().unwrap();
+        std::io::copy(input, &mut stdin)?;
+    }
+
+    if let Some(output) = output {
+        let mut stdout = child.stdout.take().unwrap();
+        std::io::copy(&mut stdout, output)?;
+    }
+
+    let status = child.wait()?;
+
+    if !status.success() {
+        return Err(Box::new(ShellError {
+            err_code: status.code().unwrap_or(1),
+            trace: format!("Command failed: {}", cmd),
+        }));
+    }
+
+    Ok(())
+}
+
+/// Runs a shell command printing every line of its output after `prefix`,
+/// so output of commands running side by side stays readable
+pub fn exec_shell_prefixed(
+    cmd: &str,
+    cwd: &str,
+    env: &HashMap<String, String>,
+    prefix: &str,
+) -> Result<(), Box<dyn Error>> {
+    let mut command = Command::new(SHELL);
+    command.arg(FLAG).arg(cmd).current_dir(cwd);
+    command.envs(env_transform_paths(env));
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = command.spawn()?;
+
+    let stderr = child.stderr.take().unwrap();
+    let stderr_prefix = prefix.to_string();
+    let stderr_thread = std::thread::spawn(move || {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            eprintln!("{} {}", stderr_prefix, line);
+        }
+    });
+
+    let stdout = child.stdout.take().unwrap();
+    for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+        println!("{} {}", prefix, line);
+    }
+
+    let _ = stderr_thread.join();
+    let status = child.wait()?;
+
+    if !status.success() {
+        return Err(Box::new(ShellError {
+            err_code: status.code().unwrap_or(1),
+            trace: format!("Command failed: {}", cmd),
+        }));
+    }
+
+    Ok(())
+}
+
+/// Asks a yes/no question, `--force` answers yes and no terminal answers no
+pub fn confirm(question: &str, options: &GlobalOptions) -> Result<bool, Box<dyn Error>> {
+    if options.force {
+        return Ok(true);
+    }
+
+    if options.no_tty || !atty::is(atty::Stream::Stdin) {
+        return Ok(false);
+    }
+
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
@@ -0,0 +1,301 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use colored::Colorize;
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+
+use crate::engine::EngineResult;
+
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+const RCODE_FORMERR: u8 = 1;
+const RCODE_REFUSED: u8 = 5;
+const TTL: u32 = 60;
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Minimal DNS responder answering every name under the workspace domain
+/// with a fixed address, other names are forwarded or refused
+pub struct DnsServer {
+    pub domain: String,
+    pub address: Ipv4Addr,
+    pub address6: Option<Ipv6Addr>,
+    pub forward: Option<SocketAddr>,
+}
+
+pub enum Reply {
+    Respond(Vec<u8>),
+    Forward,
+    Ignore,
+}
+
+struct Question {
+    name: String,
+    qtype: u16,
+    qclass: u16,
+    // Header and question section, echoed back in the response
+    end: usize,
+}
+
+impl DnsServer {
+    pub async fn run(self: Arc<Self>, listen: &str) -> EngineResult<()> {
+        let socket = UdpSocket::bind(listen).await?;
+
+        self.serve(socket).await
+    }
+
+    async fn serve(self: Arc<Self>, socket: UdpSocket) -> EngineResult<()> {
+        let socket = Arc::new(socket);
+        let mut buffer = [0u8; 512];
+
+        loop {
+            let (len, peer) = socket.recv_from(&mut buffer).await?;
+            let query = buffer[..len].to_vec();
+            let server = self.clone();
+            let socket = socket.clone();
+
+            tokio::spawn(async move {
+                let response = match server.answer(&query) {
+                    Reply::Respond(response) => Ok(response),
+                    Reply::Forward => server.forward(&query).await,
+                    Reply::Ignore => return,
+                };
+
+                let result = match response {
+                    Ok(response) => socket.send_to(&response, peer).await.map(|_| ()),
+                    Err(err) => {
+                        eprintln!("{} {}", "DNS forward failed:".bright_red(), err);
+                        return;
+                    }
+                };
+
+                if let Err(err) = result {
+                    eprintln!("{} {}", "DNS error:".bright_red(), err);
+                }
+            });
+        }
+    }
+
+    pub fn answer(&self, query: &[u8]) -> Reply {
+        // Too short to carry an id to reply to, or not a query at all
+        if query.len() < 12 || query[2] & 0x80 != 0 {
+            return Reply::Ignore;
+        }
+
+        let question = match parse_question(query) {
+            Some(question) => question,
+            None => return Reply::Respond(error_response(query, 12, RCODE_FORMERR)),
+        };
+
+        if !self.is_local(&question.name) {
+            return match self.forward {
+                Some(_) => Reply::Forward,
+                None => Reply::Respond(error_response(query, question.end, RCODE_REFUSED)),
+            };
+        }
+
+        let address = match (question.qtype, question.qclass) {
+            (TYPE_A, CLASS_IN) => Some(IpAddr::V4(self.address)),
+            (TYPE_AAAA, CLASS_IN) => self.address6.map(IpAddr::V6),
+            _ => None,
+        };
+
+        println!(
+            "{} {} -> {}",
+            type_name(question.qtype),
+            question.name,
+            address
+                .map(|address| address.to_string())
+                .unwrap_or_else(|| "no records".to_string())
+        );
+
+        let rdata = address.map(|address| match address {
+            IpAddr::V4(address) => address.octets().to_vec(),
+            IpAddr::V6(address) => address.octets().to_vec(),
+        });
+
+        let mut response = query[..question.end].to_vec();
+        // QR, AA and the client's RD flag, no recursion available
+        response[2] = 0x84 | (query[2] & 0x01);
+        response[3] = 0;
+        response[6..12].copy_from_slice(&[0, rdata.is_some() as u8, 0, 0, 0, 0]);
+
+        if let Some(rdata) = rdata {
+            // Name is a pointer to the question at offset 12
+            response.extend_from_slice(&[0xc0, 0x0c]);
+            response.extend_from_slice(&question.qtype.to_be_bytes());
+            response.extend_from_slice(&CLASS_IN.to_be_bytes());
+            response.extend_from_slice(&TTL.to_be_bytes());
+            response.extend_from_slice(&(rdata.len() as u16).to_be_bytes());
+            response.extend_from_slice(&rdata);
+        }
+
+        Reply::Respond(response)
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        let name = name.trim_end_matches('.').to_lowercase();
+        let domain = self.domain.trim_end_matches('.').to_lowercase();
+
+        name == domain || name.ends_with(&format!(".{}", domain))
+    }
+
+    async fn forward(&self, query: &[u8]) -> EngineResult<Vec<u8>> {
+        let upstream = self.forward.ok_or("No upstream to forward to")?;
+        let bind = if upstream.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+
+        let socket = UdpSocket::bind(bind).await?;
+        socket.send_to(query, upstream).await?;
+
+        let mut buffer = vec![0u8; 4096];
+        let len = timeout(FORWARD_TIMEOUT, socket.recv(&mut buffer)).await??;
+        buffer.truncate(len);
+
+        Ok(buffer)
+    }
+}
+
+fn parse_question(query: &[u8]) -> Option<Question> {
+    let qdcount = u16::from_be_bytes([query[4], query[5]]);
+    if qdcount != 1 {
+        return None;
+    }
+
+    let mut labels: Vec<String> = Vec::new();
+    let mut pos = 12;
+
+    loop {
+        let len = *query.get(pos)? as usize;
+        pos += 1;
+
+        if len == 0 {
+            break;
+        }
+
+        // Compression pointers never appear in a single question
+        if len & 0xc0 != 0 {
+            return None;
+        }
+
+        let label = query.get(pos..pos + len)?;
+        labels.push(String::from_utf8_lossy(label).to_string());
+        pos += len;
+    }
+
+    let fields = query.get(pos..pos + 4)?;
+
+    Some(Question {
+        name: labels.join("."),
+        qtype: u16::from_be_bytes([fields[0], fields[1]]),
+        qclass: u16::from_be_bytes([fields[2], fields[3]]),
+        end: pos + 4,
+    })
+}
+
+// Echoes the header and the first `end` bytes of the query, without records
+fn error_response(query: &[u8], end: usize, rcode: u8) -> Vec<u8> {
+    let mut response = query[..end].to_vec();
+
+    // QR, keeping the opcode and RD flag of the query
+    response[2] = 0x80 | (query[2] & 0x79);
+    response[3] = rcode;
+    response[4..6].copy_from_slice(&[0, (end > 12) as u8]);
+    response[6..12].fill(0);
+
+    response
+}
+
+fn type_name(qtype: u16) -> String {
+    match qtype {
+        TYPE_A => "A".to_string(),
+        TYPE_AAAA => "AAAA".to_string(),
+        other => format!("TYPE{}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(id: u16, name: &str, qtype: u16) -> Vec<u8> {
+        let mut query = id.to_be_bytes().to_vec();
+        // RD flag, one question
+        query.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]);
+
+        for label in name.split('.') {
+            query.push(label.len() as u8);
+            query.extend_from_slice(label.as_bytes());
+        }
+
+        query.push(0);
+        query.extend_from_slice(&qtype.to_be_bytes());
+        query.extend_from_slice(&CLASS_IN.to_be_bytes());
+        query
+    }
+
+    async fn ask(socket: &UdpSocket, query: &[u8]) -> Vec<u8> {
+        socket.send(query).await.unwrap();
+
+        let mut buffer = vec![0u8; 512];
+        let len = timeout(Duration::from_secs(5), socket.recv(&mut buffer))
+            .await
+            .unwrap()
+            .unwrap();
+        buffer.truncate(len);
+        buffer
+    }
+
+    // Rcode, answer count and the data of the only answer
+    fn parse_answer(query: &[u8], response: &[u8]) -> (u8, u16, Vec<u8>) {
+        assert_eq!(response[..2], query[..2]);
+        assert_eq!(response[2] & 0x80, 0x80);
+
+        let rcode = response[3] & 0x0f;
+        let answers = u16::from_be_bytes([response[6], response[7]]);
+        // Pointer, type, class, ttl and rdlength follow the echoed question
+        let rdata = response.get(query.len() + 12..).unwrap_or_default();
+
+        (rcode, answers, rdata.to_vec())
+    }
+
+    #[tokio::test]
+    async fn answers_queries_over_udp() {
+        let server = Arc::new(DnsServer {
+            domain: "uniform.test".to_string(),
+            address: Ipv4Addr::new(127, 0, 0, 1),
+            address6: Some(Ipv6Addr::LOCALHOST),
+            forward: None,
+        });
+
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let address = socket.local_addr().unwrap();
+        tokio::spawn(server.serve(socket));
+
+        let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        client.connect(address).await.unwrap();
+
+        let a = query(1, "App.uniform.test", TYPE_A);
+        assert_eq!(
+            parse_answer(&a, &ask(&client, &a).await),
+            (0, 1, vec![127, 0, 0, 1])
+        );
+
+        let aaaa = query(2, "api.uniform.test", TYPE_AAAA);
+        assert_eq!(
+            parse_answer(&aaaa, &ask(&client, &aaaa).await),
+            (0, 1, Ipv6Addr::LOCALHOST.octets().to_vec())
+        );
+
+        let other = query(3, "example.com", TYPE_A);
+        assert_eq!(
+            parse_answer(&other, &ask(&client, &other).await),
+            (RCODE_REFUSED, 0, vec![])
+        );
+    }
+}
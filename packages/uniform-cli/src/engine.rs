@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::process::Stdio;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::{
+    args::CliError,
+    core::{docker_program, GlobalOptions},
+};
+
+/// Labels uniform puts on every container it starts
+pub const WORKSPACE_LABEL: &str = "uniform.workspace";
+pub const COMPONENT_LABEL: &str = "uniform.component";
+/// Label docker compose puts on containers of a service
+pub const SERVICE_LABEL: &str = "com.docker.compose.service";
+
+pub type EngineResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerInfo {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub names: String,
+    pub image: String,
+    pub state: String,
+    pub status: String,
+    pub ports: String,
+    pub running_for: String,
+    #[serde(deserialize_with = "deserialize_labels")]
+    pub labels: HashMap<String, String>,
+}
+
+impl ContainerInfo {
+    pub fn component(&self) -> Option<&str> {
+        self.labels.get(COMPONENT_LABEL).map(|s| s.as_str())
+    }
+
+    pub fn service(&self) -> Option<&str> {
+        self.labels.get(SERVICE_LABEL).map(|s| s.as_str())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state == "running"
+    }
+
+    pub fn short_id(&self) -> String {
+        self.id.chars().take(12).collect()
+    }
+
+    /// Health as reported in the status line, e.g. "Up 2 hours (healthy)".
+    /// Exit codes like "Exited (137) 2 hours ago" are not a health.
+    pub fn health(&self) -> Option<&str> {
+        if !self.is_running() {
+            return None;
+        }
+
+        let start = self.status.rfind('(')?;
+        let end = self.status.rfind(')')?;
+
+        match self.status.get(start + 1..end)? {
+            "healthy" => Some("healthy"),
+            "unhealthy" => Some("unhealthy"),
+            "health: starting" => Some("starting"),
+            _ => None,
+        }
+    }
+
+    pub fn uptime(&self) -> Option<&str> {
+        if !self.is_running() {
+            return None;
+        }
+
+        let uptime = self.status.strip_prefix("Up ")?;
+
+        Some(uptime.split(" (").next().unwrap_or(uptime))
+    }
+
+    /// Host port `port` is published on, from "0.0.0.0:8080->80/tcp"
+    pub fn published_port(&self, port: u16) -> Option<u16> {
+        let suffix = format!("->{}/tcp", port);
+
+        self.ports
+            .split(", ")
+            .filter_map(|mapping| mapping.strip_suffix(&suffix))
+            .filter_map(|host| host.rsplit(':').next()?.parse().ok())
+            .next()
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ContainerStats {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "CPUPerc")]
+    pub cpu: String,
+    #[serde(rename = "MemUsage")]
+    pub memory: String,
+}
+
+/// Container engine uniform talks to for status queries and component lifecycle
+#[async_trait]
+pub trait Engine: Send + Sync {
+    /// Lists all containers, running or not, labelled with the workspace name
+    async fn list_containers(&self, workspace: &str) -> EngineResult<Vec<ContainerInfo>>;
+
+    /// One-shot CPU/memory usage of running containers
+    async fn stats(&self, ids: &[String]) -> EngineResult<Vec<ContainerStats>>;
+
+    /// IP address of the container, on `network` when it is attached to it
+    async fn container_address(
+        &self,
+        id: &str,
+        network: Option<&str>,
+    ) -> EngineResult<Option<String>>;
+
+    /// Last `tail` lines of container output
+    async fn logs(&self, id: &str, tail: usize) -> EngineResult<Vec<String>>;
+
+    async fn start_component(&self, name: &str) -> EngineResult<()>;
+
+    async fn stop_component(&self, name: &str) -> EngineResult<()>;
+
+    async fn restart_component(&self, name: &str) -> EngineResult<()> {
+        self.stop_component(name).await?;
+        self.start_component(name).await
+    }
+
+    /// Opens an interactive shell in the container as `user`, blocking until it exits
+    fn exec_shell(&self, id: &str, user: &str, working_dir: Option<&str>) -> EngineResult<()>;
+}
+
+pub fn create_engine(options: &GlobalOptions) -> Arc<dyn Engine> {
+    Arc::new(DockerEngine::new(options))
+}
+
+#[derive(Default)]
+pub struct DockerEngine {
+    /// Global options passed on to the CLI for component lifecycle
+    global_args: Vec<String>,
+}
+
+impl DockerEngine {
+    pub fn new(options: &GlobalOptions) -> DockerEngine {
+        let mut global_args = vec!["--no-tty".to_string()];
+
+        if let Some(mode) = &options.mode {
+            global_args.extend(["--mode".to_string(), mode.to_string()]);
+        }
+        if let Some(uid) = options.uid {
+            global_args.extend(["--uid".to_string(), uid.to_string()]);
+        }
+        if options.force {
+            global_args.push("--force".to_string());
+        }
+        if options.dry_run {
+            global_args.push("--dry-run".to_string());
+        }
+
+        DockerEngine { global_args }
+    }
+
+    async fn docker(&self, args: &[&str]) -> EngineResult<String> {
+        let output = Command::new(docker_program()).args(args).output().await?;
+
+        if !output.status.success() {
+            return Err(Box::new(CliError::new(String::from_utf8_lossy(
+                &output.stderr,
+            ))));
+        }
+
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    // Component lifecycle goes through the CLI itself, so dependencies,
+    // labels and workspace state are handled the same way as on the command line
+    async fn uniform(&self, args: &[&str]) -> EngineResult<()> {
+        let output = Command::new(std::env::current_exe()?)
+            .args(&self.global_args)
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            return Err(Box::new(CliError::new(String::from_utf8_lossy(
+                &output.stderr,
+            ))));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Engine for DockerEngine {
+    async fn list_containers(&self, workspace: &str) -> EngineResult<Vec<ContainerInfo>> {
+        let filter = format!("label={}={}", WORKSPACE_LABEL, workspace);
+        let stdout = self
+            .docker(&[
+                "ps",
+                "--all",
+                "--no-trunc",
+                "--filter",
+                &filter,
+                "--format",
+                "{{json .}}",
+            ])
+            .await?;
+
+        let mut containers: Vec<ContainerInfo> = Vec::new();
+
+        for line in stdout.lines().filter(|line| !line.trim().is_empty()) {
+            containers.push(serde_json::from_str(line)?);
+        }
+
+        if containers.is_empty() {
+            return Ok(containers);
+        }
+
+        // ps joins labels with commas that values like the compose file list
+        // contain too, inspect has them as a map
+        let mut args = vec!["inspect", "--format", "{{.Id}} {{json .Config.Labels}}"];
+        args.extend(containers.iter().map(|container| container.id.as_str()));
+
+        let stdout = self.docker(&args).await?;
+        let mut labels: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+        for line in stdout.lines() {
+            if let Some((id, json)) = line.split_once(' ') {
+                labels.insert(id.to_string(), serde_json::from_str(json)?);
+            }
+        }
+
+        for container in containers.iter_mut() {
+            if let Some(labels) = labels.remove(&container.id) {
+                container.labels = labels;
+            }
+        }
+
+        Ok(containers)
+    }
+
+    async fn stats(&self, ids: &[String]) -> EngineResult<Vec<ContainerStats>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut args = vec!["stats", "--no-stream", "--no-trunc", "--format", "{{json .}}"];
+        args.extend(ids.iter().map(|id| id.as_str()));
+
+        let stdout = self.docker(&args).await?;
+        let mut stats = Vec::new();
+
+        for line in stdout.lines().filter(|line| !line.trim().is_empty()) {
+            stats.push(serde_json::from_str(line)?);
+        }
+
+        Ok(stats)
+    }
+
+    async fn container_address(
+        &self,
+        id: &str,
+        network: Option<&str>,
+    ) -> EngineResult<Option<String>> {
+        let stdout = self
+            .docker(&["inspect", "--format", "{{json .NetworkSettings.Networks}}", id])
+            .await?;
+        let networks: HashMap<String, serde_json::Value> = serde_json::from_str(stdout.trim())?;
+
+        let mut addresses = networks.iter().filter_map(|(name, settings)| {
+            let ip = settings.get("IPAddress")?.as_str()?;
+
+            (!ip.is_empty()).then(|| (name.as_str(), ip.to_string()))
+        });
+
+        let address = match network {
+            Some(network) => addresses
+                .clone()
+                .find(|(name, _)| *name == network)
+                .or_else(|| addresses.next()),
+            None => addresses.next(),
+        };
+
+        Ok(address.map(|(_, ip)| ip))
+    }
+
+    async fn logs(&self, id: &str, tail: usize) -> EngineResult<Vec<String>> {
+        let output = Command::new(docker_program())
+            .arg("logs")
+            .arg("--tail")
+            .arg(tail.to_string())
+            .arg(id)
+            .output()
+            .await?;
+
+        // docker replays container stderr on its own stderr
+        let mut lines: Vec<String> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+        lines.extend(
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .map(|line| line.to_string()),
+        );
+
+        Ok(lines)
+    }
+
+    async fn start_component(&self, name: &str) -> EngineResult<()> {
+        self.uniform(&["start", name]).await
+    }
+
+    async fn stop_component(&self, name: &str) -> EngineResult<()> {
+        self.uniform(&["stop", name]).await
+    }
+
+    fn exec_shell(&self, id: &str, user: &str, working_dir: Option<&str>) -> EngineResult<()> {
+        let mut command = std::process::Command::new(docker_program());
+        command.args(["exec", "-it", "-u", user]);
+
+        if let Some(working_dir) = working_dir {
+            command.args(["-w", working_dir]);
+        }
+
+        command.args([id, "sh"]).status()?;
+
+        Ok(())
+    }
+}
+
+// docker prints labels as a single "key=value,key=value" string. A part
+// without "=" continues the value before it, like the second file in
+// "com.docker.compose.project.config_files=a.yml,b.json".
+pub fn deserialize_labels<'de, D>(deserializer: D) -> Result<HashMap<String, String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let labels = String::deserialize(deserializer)?;
+
+    Ok(parse_labels(&labels))
+}
+
+fn parse_labels(labels: &str) -> HashMap<String, String> {
+    let mut result: Vec<(String, String)> = Vec::new();
+
+    for part in labels.split(',').filter(|part| !part.is_empty()) {
+        match (part.split_once('='), result.last_mut()) {
+            (Some((key, value)), _) => result.push((key.to_string(), value.to_string())),
+            (None, Some((_, value))) => {
+                value.push(',');
+                value.push_str(part);
+            }
+            (None, None) => {}
+        }
+    }
+
+    result.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn container(state: &str, status: &str) -> ContainerInfo {
+        ContainerInfo {
+            state: state.to_string(),
+            status: status.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn health_of_running_containers() {
+        assert_eq!(
+            container("running", "Up 2 hours (healthy)").health(),
+            Some("healthy")
+        );
+        assert_eq!(
+            container("running", "Up 5 seconds (health: starting)").health(),
+            Some("starting")
+        );
+        assert_eq!(
+            container("running", "Up 1 minute (unhealthy)").health(),
+            Some("unhealthy")
+        );
+        assert_eq!(container("running", "Up 2 hours").health(), None);
+    }
+
+    #[test]
+    fn label_values_keep_their_commas() {
+        let labels = parse_labels(
+            "com.docker.compose.project.config_files=/app/docker-compose.yml,/home/.uniform/compose/app.labels.json,com.docker.compose.service=app,uniform.component=app",
+        );
+
+        assert_eq!(
+            labels["com.docker.compose.project.config_files"],
+            "/app/docker-compose.yml,/home/.uniform/compose/app.labels.json"
+        );
+        assert_eq!(labels[SERVICE_LABEL], "app");
+        assert_eq!(labels[COMPONENT_LABEL], "app");
+    }
+
+    #[test]
+    fn exit_code_is_not_health() {
+        assert_eq!(
+            container("exited", "Exited (137) 2 hours ago").health(),
+            None
+        );
+        assert_eq!(
+            container("restarting", "Restarting (1) 3 seconds ago").health(),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn exec_shell_runs_as_user_in_working_dir() {
+        use crate::core::TEST_DOCKER;
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("uniform-engine-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let docker = dir.join("docker");
+        let args = dir.join("args");
+        fs::write(
+            &docker,
+            format!("#!/bin/sh\necho \"$*\" > {}\n", args.display()),
+        )
+        .unwrap();
+        fs::set_permissions(&docker, fs::Permissions::from_mode(0o755)).unwrap();
+        TEST_DOCKER.with(|program| *program.borrow_mut() = Some(docker.display().to_string()));
+
+        let engine = DockerEngine::default();
+        engine
+            .exec_shell("abc123", "1000:1000", Some("/app"))
+            .unwrap();
+        let with_dir = fs::read_to_string(&args).unwrap();
+        engine.exec_shell("abc123", "0", None).unwrap();
+        let without_dir = fs::read_to_string(&args).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(with_dir.trim(), "exec -it -u 1000:1000 -w /app abc123 sh");
+        assert_eq!(without_dir.trim(), "exec -it -u 0 abc123 sh");
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::engine::{
+    ContainerInfo, ContainerStats, Engine, EngineResult, COMPONENT_LABEL, SERVICE_LABEL,
+    WORKSPACE_LABEL,
+};
+
+/// In-process engine keeping containers in memory, for tests
+#[derive(Default)]
+pub struct FakeEngine {
+    pub containers: Mutex<Vec<ContainerInfo>>,
+    /// Error container queries fail with, like a stopped docker daemon
+    pub error: Mutex<Option<String>>,
+    /// Number of container queries made
+    pub queries: AtomicUsize,
+}
+
+impl FakeEngine {
+    pub fn new() -> FakeEngine {
+        FakeEngine::default()
+    }
+
+    pub fn add_container(&self, workspace: &str, component: &str, service: &str, running: bool) {
+        let mut containers = self.containers.lock().unwrap();
+        let id = format!("fake{:012x}", containers.len());
+
+        let mut labels = HashMap::new();
+        labels.insert(WORKSPACE_LABEL.to_string(), workspace.to_string());
+        labels.insert(COMPONENT_LABEL.to_string(), component.to_string());
+        labels.insert(SERVICE_LABEL.to_string(), service.to_string());
+
+        containers.push(ContainerInfo {
+            id,
+            names: format!("{}-{}-1", component, service),
+            image: format!("{}:latest", component),
+            state: if running { "running" } else { "exited" }.to_string(),
+            status: if running { "Up 1 second" } else { "Exited (0)" }.to_string(),
+            ports: if running { "0.0.0.0:8080->80/tcp" } else { "" }.to_string(),
+            labels,
+            ..Default::default()
+        });
+    }
+
+    fn set_running(&self, name: &str, running: bool) {
+        let mut containers = self.containers.lock().unwrap();
+
+        for container in containers
+            .iter_mut()
+            .filter(|c| c.component() == Some(name))
+        {
+            container.state = if running { "running" } else { "exited" }.to_string();
+            container.status = if running { "Up 1 second" } else { "Exited (0)" }.to_string();
+        }
+    }
+}
+
+#[async_trait]
+impl Engine for FakeEngine {
+    async fn list_containers(&self, workspace: &str) -> EngineResult<Vec<ContainerInfo>> {
+        self.queries.fetch_add(1, Ordering::SeqCst);
+
+        if let Some(error) = self.error.lock().unwrap().clone() {
+            return Err(error.into());
+        }
+
+        Ok(self
+            .containers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|c| c.labels.get(WORKSPACE_LABEL).map(|w| w.as_str()) == Some(workspace))
+            .cloned()
+            .collect())
+    }
+
+    async fn stats(&self, ids: &[String]) -> EngineResult<Vec<ContainerStats>> {
+        Ok(ids
+            .iter()
+            .map(|id| ContainerStats {
+                id: id.clone(),
+                cpu: "0.00%".to_string(),
+                memory: "1MiB / 1GiB".to_string(),
+            })
+            .collect())
+    }
+
+    async fn container_address(
+        &self,
+        _id: &str,
+        _network: Option<&str>,
+    ) -> EngineResult<Option<String>> {
+        Ok(Some("127.0.0.1".to_string()))
+    }
+
+    async fn logs(&self, id: &str, _tail: usize) -> EngineResult<Vec<String>> {
+        Ok(vec![format!("fake logs of {}", id)])
+    }
+
+    async fn start_component(&self, name: &str) -> EngineResult<()> {
+        self.set_running(name, true);
+
+        Ok(())
+    }
+
+    async fn stop_component(&self, name: &str) -> EngineResult<()> {
+        self.set_running(name, false);
+
+        Ok(())
+    }
+
+    fn exec_shell(&self, _id: &str, _user: &str, _working_dir: Option<&str>) -> EngineResult<()> {
+        Ok(())
+    }
+}
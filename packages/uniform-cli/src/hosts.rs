@@ -0,0 +1,232 @@
+use std::error::Error;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::{args::CliError, proxy::collect_routes, workspace::Workspace};
+
+const HOSTS_ADDRESS: &str = "127.0.0.1";
+
+/// Hosts file to manage: `--file`, then `UNIFORM_HOSTS_FILE`, then the system one
+pub fn hosts_file_path(file: Option<&String>) -> PathBuf {
+    if let Some(file) = file {
+        return PathBuf::from(file);
+    }
+
+    if let Ok(file) = std::env::var("UNIFORM_HOSTS_FILE") {
+        return PathBuf::from(file);
+    }
+
+    if cfg!(target_family = "windows") {
+        PathBuf::from(r"C:\Windows\System32\drivers\etc\hosts")
+    } else {
+        PathBuf::from("/etc/hosts")
+    }
+}
+
+/// Managed block of one workspace, so several workspaces can share a hosts file
+pub struct HostsBlock {
+    begin: String,
+    end: String,
+    pub hostnames: Vec<String>,
+}
+
+impl HostsBlock {
+    pub fn new(workspace: &Workspace) -> Result<HostsBlock, Box<dyn Error>> {
+        let name = &workspace.config.as_ref().unwrap().name;
+        let mut hostnames: Vec<String> = Vec::new();
+
+        for route in collect_routes(workspace)? {
+            if !hostnames.contains(&route.hostname) {
+                hostnames.push(route.hostname);
+            }
+        }
+
+        Ok(HostsBlock::with_hostnames(name, hostnames))
+    }
+
+    pub fn with_hostnames(name: &str, hostnames: Vec<String>) -> HostsBlock {
+        HostsBlock {
+            begin: format!("# BEGIN uniform {}", name),
+            end: format!("# END uniform {}", name),
+            hostnames,
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut lines = vec![
+            self.begin.clone(),
+            "# Managed by `uniform hosts sync`, do not edit".to_string(),
+        ];
+
+        for hostname in &self.hostnames {
+            lines.push(format!("{} {}", HOSTS_ADDRESS, hostname));
+        }
+
+        lines.push(self.end.clone());
+        lines.join("\n")
+    }
+
+    /// Hostnames currently listed in the block of `content`
+    pub fn parse(&self, content: &str) -> Option<Vec<String>> {
+        let (_, block, _) = self.split(content)?;
+
+        Some(
+            block
+                .lines()
+                .filter(|line| !line.trim_start().starts_with('#'))
+                .filter_map(|line| line.split_whitespace().nth(1))
+                .map(|hostname| hostname.to_string())
+                .collect(),
+        )
+    }
+
+    /// Content with the block replaced, or appended when missing
+    pub fn apply(&self, content: &str) -> String {
+        let line_ending = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let block = self.render().replace('\n', line_ending);
+
+        match self.split(content) {
+            Some((before, _, after)) => format!("{}{}{}", before, block, after),
+            None if content.is_empty() => format!("{}{}", block, line_ending),
+            None if content.ends_with('\n') => format!("{}{}{}", content, block, line_ending),
+            None => format!("{}{}{}{}", content, line_ending, block, line_ending),
+        }
+    }
+
+    /// Content without the block, `None` if there is none
+    pub fn remove(&self, content: &str) -> Option<String> {
+        let (before, _, after) = self.split(content)?;
+        let after = after
+            .strip_prefix("\r\n")
+            .or_else(|| after.strip_prefix('\n'))
+            .unwrap_or(after);
+
+        Some(format!("{}{}", before, after))
+    }
+
+    // (before the block, the block itself, after the block)
+    fn split<'a>(&self, content: &'a str) -> Option<(&'a str, &'a str, &'a str)> {
+        let start = find_line(content, &self.begin, 0)?;
+        let end = find_line(content, &self.end, start)? + self.end.len();
+
+        Some((&content[..start], &content[start..end], &content[end..]))
+    }
+}
+
+/// Offset of the first line at or after `from` that is exactly `marker`, so
+/// the block of workspace "app" is not mistaken for that of "app-2"
+fn find_line(content: &str, marker: &str, from: usize) -> Option<usize> {
+    let mut offset = from;
+
+    for line in content[from..].split_inclusive('\n') {
+        if line.trim_end_matches(['\r', '\n']) == marker {
+            return Some(offset);
+        }
+
+        offset += line.len();
+    }
+
+    None
+}
+
+pub fn read_hosts_file(path: &Path) -> Result<String, Box<dyn Error>> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(content),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(String::new()),
+        Err(err) => Err(Box::new(err)),
+    }
+}
+
+pub fn write_hosts_file(path: &Path, content: &str) -> Result<(), Box<dyn Error>> {
+    std::fs::write(path, content).map_err(|err| -> Box<dyn Error> {
+        if err.kind() == ErrorKind::PermissionDenied {
+            Box::new(CliError::new(format!(
+                "Permission denied writing {}, run with sudo or point --file/UNIFORM_HOSTS_FILE elsewhere",
+                path.display()
+            )))
+        } else {
+            Box::new(err)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(name: &str, hostnames: &[&str]) -> HostsBlock {
+        HostsBlock::with_hostnames(name, hostnames.iter().map(|h| h.to_string()).collect())
+    }
+
+    fn temp_hosts_file(test: &str, content: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("uniform-hosts-{}-{}", std::process::id(), test));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn split_matches_whole_marker_lines() {
+        let other = block("app-2", &["b.localhost"]).render();
+        let content = format!("127.0.0.1 localhost\n{}\n", other);
+
+        assert!(block("app", &[]).split(&content).is_none());
+        assert_eq!(
+            block("app-2", &[]).parse(&content),
+            Some(vec!["b.localhost".to_string()])
+        );
+    }
+
+    #[test]
+    fn sync_keeps_prefixed_workspace_block() {
+        let other = block("app-2", &["b.localhost"]).render();
+        let path = temp_hosts_file("sync", &format!("127.0.0.1 localhost\n{}\n", other));
+
+        let app = block("app", &["a.localhost"]);
+        let content = read_hosts_file(&path).unwrap();
+        write_hosts_file(&path, &app.apply(&content)).unwrap();
+
+        let content = read_hosts_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            content,
+            format!("127.0.0.1 localhost\n{}\n{}\n", other, app.render())
+        );
+        assert_eq!(app.parse(&content), Some(vec!["a.localhost".to_string()]));
+
+        let updated = block("app", &["c.localhost"]).apply(&content);
+        assert_eq!(
+            updated,
+            format!(
+                "127.0.0.1 localhost\n{}\n{}\n",
+                other,
+                block("app", &["c.localhost"]).render()
+            )
+        );
+    }
+
+    #[test]
+    fn clean_removes_only_own_block() {
+        let app = block("app", &["a.localhost"]).render();
+        let other = block("app-2", &["b.localhost"]).render();
+        let path = temp_hosts_file(
+            "clean",
+            &format!("127.0.0.1 localhost\r\n{}\r\n{}\r\n", other, app),
+        );
+
+        let content = read_hosts_file(&path).unwrap();
+        let cleaned = block("app", &[]).remove(&content).unwrap();
+        write_hosts_file(&path, &cleaned).unwrap();
+
+        let content = read_hosts_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(content, format!("127.0.0.1 localhost\r\n{}\r\n", other));
+        assert!(block("app", &[]).remove(&content).is_none());
+    }
+}
@@ -0,0 +1,57 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::ExecuteTrait,
+    core::GlobalOptions,
+    hosts::{hosts_file_path, read_hosts_file, write_hosts_file, HostsBlock},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct CleanCommand {
+    /// Hosts file to update, defaults to UNIFORM_HOSTS_FILE or the system hosts file
+    #[arg(long)]
+    file: Option<String>,
+}
+
+impl ExecuteTrait for CleanCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let hosts_path = hosts_file_path(self.file.as_ref());
+        let content = read_hosts_file(&hosts_path)?;
+        let block = HostsBlock::new(&ws)?;
+
+        let cleaned = match block.remove(&content) {
+            Some(cleaned) => cleaned,
+            None => {
+                println!("No uniform entries in {}", hosts_path.display());
+                return Ok(None);
+            }
+        };
+
+        if global_options.dry_run {
+            println!("{}", cleaned);
+            return Ok(None);
+        }
+
+        write_hosts_file(&hosts_path, &cleaned)?;
+
+        println!(
+            "Removed uniform entries from {}",
+            hosts_path.display().to_string().bright_green()
+        );
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,91 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::GlobalOptions,
+    hosts::{hosts_file_path, read_hosts_file, write_hosts_file, HostsBlock},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct SyncCommand {
+    /// Hosts file to update, defaults to UNIFORM_HOSTS_FILE or the system hosts file
+    #[arg(long)]
+    file: Option<String>,
+
+    /// Only report entries that are missing or stale, without writing
+    #[arg(long)]
+    check: bool,
+}
+
+impl ExecuteTrait for SyncCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let hosts_path = hosts_file_path(self.file.as_ref());
+        let content = read_hosts_file(&hosts_path)?;
+        let block = HostsBlock::new(&ws)?;
+
+        if self.check {
+            let current = block.parse(&content).unwrap_or_default();
+            let missing: Vec<&String> = block
+                .hostnames
+                .iter()
+                .filter(|hostname| !current.contains(hostname))
+                .collect();
+            let stale: Vec<&String> = current
+                .iter()
+                .filter(|hostname| !block.hostnames.contains(hostname))
+                .collect();
+
+            for hostname in &missing {
+                println!("{} {}", "+".bright_green(), hostname);
+            }
+            for hostname in &stale {
+                println!("{} {}", "-".bright_red(), hostname);
+            }
+
+            if missing.is_empty() && stale.is_empty() {
+                println!("{} is up to date", hosts_path.display());
+                return Ok(None);
+            }
+
+            return Err(Box::new(CliError::new(format!(
+                "{} is out of date, run `uniform hosts sync`",
+                hosts_path.display()
+            ))));
+        }
+
+        let updated = block.apply(&content);
+
+        if updated == content {
+            println!("{} is up to date", hosts_path.display());
+            return Ok(None);
+        }
+
+        if global_options.dry_run {
+            println!("{}", updated);
+            return Ok(None);
+        }
+
+        write_hosts_file(&hosts_path, &updated)?;
+
+        println!(
+            "Wrote {} hostnames to {}",
+            block.hostnames.len(),
+            hosts_path.display().to_string().bright_green()
+        );
+
+        Ok(None)
+    }
+}
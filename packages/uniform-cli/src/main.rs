@@ -3,13 +3,34 @@
 extern crate prettytable;
 
 mod args;
+pub mod backup;
+pub mod backup_commands;
+pub mod certs;
+pub mod certs_commands;
 pub mod commands;
 pub mod component;
 pub mod component_config;
 pub mod context;
 pub mod core;
+pub mod dns;
+pub mod engine;
+#[cfg(test)]
+pub mod fake_engine;
+pub mod hosts;
+pub mod hosts_commands;
+pub mod network_commands;
+pub mod plan;
 pub mod project_commands;
+pub mod proxy;
+pub mod resources;
+pub mod script;
+pub mod session;
+pub mod session_commands;
 pub mod settings;
+pub mod state;
+pub mod status;
+pub mod volume_commands;
+pub mod watch;
 pub mod workspace;
 pub mod workspace_config;
 
@@ -19,6 +40,7 @@ use colored::Colorize;
 
 use crate::{
     args::{parse_global_options, ExecuteTrait},
+    core::ShellError,
     settings::Settings,
 };
 
@@ -35,5 +57,12 @@ fn main() {
 
     if let Err(err) = result {
         println!("{} {:#?}", "Error:".red(), err);
+
+        // Exit code of a failed command run on behalf of the user is passed on
+        let code = err
+            .downcast_ref::<ShellError>()
+            .map(|err| err.err_code)
+            .unwrap_or(1);
+        std::process::exit(code);
     }
 }
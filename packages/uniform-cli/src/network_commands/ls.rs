@@ -0,0 +1,56 @@
+use clap::Args;
+use std::error::Error;
+
+use prettytable::{format, Table};
+
+use crate::{
+    args::ExecuteTrait,
+    core::GlobalOptions,
+    resources::{declared_networks, list_networks},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct NetworkLsCommand {}
+
+impl ExecuteTrait for NetworkLsCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let declared = declared_networks(&ws);
+        let networks = list_networks(&ws)?;
+
+        let mut table = Table::new();
+        table.set_titles(row!["Name", "Driver", "Status"]);
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        for (name, config) in &declared {
+            match networks.iter().find(|network| network.name == *name) {
+                Some(network) => table.add_row(row![name, network.driver, "Created"]),
+                None => table.add_row(row![
+                    name,
+                    config.driver.clone().unwrap_or_default(),
+                    "Missing"
+                ]),
+            };
+        }
+
+        for network in &networks {
+            if !declared.iter().any(|(name, _)| *name == network.name) {
+                table.add_row(row![network.name, network.driver, "Undeclared"]);
+            }
+        }
+
+        table.printstd();
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,2 @@
+pub mod ls;
+pub mod prune;
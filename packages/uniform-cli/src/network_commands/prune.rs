@@ -0,0 +1,64 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::ExecuteTrait,
+    core::GlobalOptions,
+    resources::{declared_networks, list_networks, remove_network},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+/// Removes networks created for the workspace that are no longer declared
+#[derive(Debug, Args)]
+pub struct NetworkPruneCommand {
+    /// Remove declared networks as well
+    #[arg(long)]
+    all: bool,
+}
+
+impl ExecuteTrait for NetworkPruneCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let declared = declared_networks(&ws);
+        let networks = list_networks(&ws)?;
+        let mut removed = 0;
+
+        for network in networks {
+            let is_declared = declared.iter().any(|(name, _)| *name == network.name);
+
+            if is_declared && !self.all {
+                continue;
+            }
+
+            if global_options.dry_run {
+                println!("Would remove network {}", network.name);
+                continue;
+            }
+
+            // Networks still in use are kept, docker refuses to remove them
+            match remove_network(&network.name) {
+                Ok(()) => {
+                    println!("Removed network {}", network.name.bright_green());
+                    removed += 1;
+                }
+                Err(err) => println!("{} {}", "Kept network:".bright_yellow(), err),
+            }
+        }
+
+        if removed == 0 && !global_options.dry_run {
+            println!("Nothing to prune");
+        }
+
+        Ok(None)
+    }
+}
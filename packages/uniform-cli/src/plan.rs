@@ -0,0 +1,752 @@
+use std::collections::HashSet;
+use std::error::Error;
+
+use colored::Colorize;
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::{
+    args::CliError,
+    component::Component,
+    component_config::{get_deps, HookEvent},
+    core::{GlobalOptions, Mode},
+    resources::{ensure_resources, missing_resources},
+    state::WorkspaceState,
+    status::WorkspaceStatus,
+    workspace::{Target, Workspace},
+};
+
+/// Context keys that are always shown for a plan step, besides the
+/// variables declared by the component itself and its template
+const PLAN_ENV_KEYS: [&str; 5] = [
+    "APP_NAME",
+    "COMPOSE_PROJECT_NAME",
+    "COMPOSE_FILE",
+    "SVC_PATH",
+    "TPL_PATH",
+];
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanAction {
+    Start,
+    Stop,
+    Recreate,
+}
+
+impl PlanAction {
+    fn compose_command(&self, service: Option<&String>) -> Vec<String> {
+        let mut command = match self {
+            PlanAction::Start => vec!["up".to_string(), "-d".to_string()],
+            PlanAction::Stop => vec!["stop".to_string()],
+            PlanAction::Recreate => vec![
+                "up".to_string(),
+                "-d".to_string(),
+                "--force-recreate".to_string(),
+            ],
+        };
+        command.extend(service.cloned());
+
+        command
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PlanReason {
+    Requested,
+    Dependency { of: String, mode: Mode },
+    Host { of: String },
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanStep {
+    pub component: String,
+    /// Single compose service of the component, all of them if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    pub action: PlanAction,
+    pub reason: PlanReason,
+    pub running: bool,
+    /// Component is already in the desired state and the step won't run
+    pub skip: bool,
+    pub command: Vec<String>,
+    pub env: IndexMap<String, String>,
+}
+
+/// Ordered list of engine calls a start/stop request is going to make
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct Plan {
+    pub steps: Vec<PlanStep>,
+    /// Declared networks created before the first start
+    pub networks: Vec<String>,
+    /// Declared volumes created before the first start
+    pub volumes: Vec<String>,
+}
+
+impl Plan {
+    /// Plans a start of the given components, dependencies of the current mode first
+    pub fn start(
+        workspace: &Workspace,
+        targets: &[Target],
+        options: &GlobalOptions,
+    ) -> Result<Plan, Box<dyn Error>> {
+        let status = WorkspaceStatus::load(workspace, options)?;
+
+        Plan::start_with_status(workspace, targets, options, &status)?.with_resources(workspace)
+    }
+
+    fn start_with_status(
+        workspace: &Workspace,
+        targets: &[Target],
+        options: &GlobalOptions,
+        status: &WorkspaceStatus,
+    ) -> Result<Plan, Box<dyn Error>> {
+        let mut plan = Plan::default();
+        let mut visited = HashSet::new();
+
+        for target in targets {
+            plan.visit_start(
+                workspace,
+                target,
+                PlanReason::Requested,
+                options,
+                status,
+                &mut visited,
+            )?;
+        }
+
+        Ok(plan)
+    }
+
+    /// Plans a start of the dependencies of a component in the current mode,
+    /// without the component itself
+    pub fn dependencies(
+        workspace: &Workspace,
+        name: &str,
+        options: &GlobalOptions,
+    ) -> Result<Plan, Box<dyn Error>> {
+        let mode = options.mode.clone().unwrap_or(Mode::Default);
+        let status = WorkspaceStatus::load(workspace, options)?;
+        let mut plan = Plan::default();
+        let mut visited = HashSet::new();
+
+        let component = find_component(workspace, name)?;
+        visited.insert(Target {
+            component: name.to_string(),
+            service: None,
+        });
+
+        let mut deps = get_deps(&component.config, &mode);
+        deps.sort();
+
+        for dep in deps {
+            plan.visit_start(
+                workspace,
+                &Target {
+                    component: dep,
+                    service: None,
+                },
+                PlanReason::Dependency {
+                    of: name.to_string(),
+                    mode: mode.clone(),
+                },
+                options,
+                &status,
+                &mut visited,
+            )?;
+        }
+
+        plan.with_resources(workspace)
+    }
+
+    /// Plans a stop of the given components. Dependencies are left running.
+    pub fn stop(
+        workspace: &Workspace,
+        targets: &[Target],
+        options: &GlobalOptions,
+    ) -> Result<Plan, Box<dyn Error>> {
+        let status = WorkspaceStatus::load(workspace, options)?;
+
+        Plan::stop_with_status(workspace, targets, &status)
+    }
+
+    fn stop_with_status(
+        workspace: &Workspace,
+        targets: &[Target],
+        status: &WorkspaceStatus,
+    ) -> Result<Plan, Box<dyn Error>> {
+        let mut plan = Plan::default();
+
+        for target in targets {
+            let component = find_component(workspace, &target.component)?;
+
+            // Hosted components are stopped together with their host
+            if component.config.hosted_in.is_some() {
+                continue;
+            }
+
+            let planned = plan
+                .steps
+                .iter()
+                .any(|step| step.component == target.component && step.service == target.service);
+
+            if planned {
+                continue;
+            }
+
+            let running = status.is_service_running(&target.component, target.service.as_deref());
+
+            plan.steps.push(PlanStep {
+                component: target.component.clone(),
+                service: target.service.clone(),
+                action: PlanAction::Stop,
+                reason: PlanReason::Requested,
+                running,
+                skip: !running,
+                command: component
+                    .compose_command(&PlanAction::Stop.compose_command(target.service.as_ref())),
+                env: plan_env(component),
+            });
+        }
+
+        Ok(plan)
+    }
+
+    /// Plans a recreate of tracked components whose effective config changed
+    /// since they were started. Empty `names` means the whole workspace.
+    pub fn changed(
+        workspace: &Workspace,
+        names: &[String],
+        options: &GlobalOptions,
+    ) -> Result<Plan, Box<dyn Error>> {
+        let state = WorkspaceState::load(workspace)?;
+        let status = WorkspaceStatus::load(workspace, options)?;
+        let mut plan = Plan::default();
+
+        for name in state.components.keys() {
+            if !names.is_empty() && !names.contains(name) {
+                continue;
+            }
+
+            let component = match workspace.components.get(name) {
+                Some(component) => component,
+                None => continue,
+            };
+
+            if !state.is_stale(component) {
+                continue;
+            }
+
+            let running = status.is_running(name);
+
+            plan.steps.push(PlanStep {
+                component: name.clone(),
+                service: None,
+                action: PlanAction::Recreate,
+                reason: PlanReason::Changed,
+                running,
+                skip: !running,
+                command: component.compose_command(&PlanAction::Recreate.compose_command(None)),
+                env: plan_env(component),
+            });
+        }
+
+        plan.with_resources(workspace)
+    }
+
+    /// Lists the declared networks and volumes that don't exist yet when
+    /// the plan starts anything
+    fn with_resources(mut self, workspace: &Workspace) -> Result<Plan, Box<dyn Error>> {
+        if !self.starts_anything() {
+            return Ok(self);
+        }
+
+        let missing = missing_resources(workspace)?;
+        self.networks = missing.networks.into_iter().map(|(name, _)| name).collect();
+        self.volumes = missing.volumes.into_iter().map(|(name, _)| name).collect();
+
+        Ok(self)
+    }
+
+    fn starts_anything(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| !step.skip && step.action != PlanAction::Stop)
+    }
+
+    fn visit_start(
+        &mut self,
+        workspace: &Workspace,
+        target: &Target,
+        reason: PlanReason,
+        options: &GlobalOptions,
+        status: &WorkspaceStatus,
+        visited: &mut HashSet<Target>,
+    ) -> Result<(), Box<dyn Error>> {
+        if !visited.insert(target.clone()) {
+            return Ok(());
+        }
+
+        let mode = &options.mode.clone().unwrap_or(Mode::Default);
+
+        let name = &target.component;
+        let service = target.service.as_deref();
+        let component = find_component(workspace, name)?;
+
+        // Hosted components have no containers of their own, starting one
+        // means starting its dependencies and the host
+        if let Some(host) = &component.config.hosted_in {
+            let mut deps = get_deps(&component.config, mode);
+            deps.sort();
+
+            let deps = deps.into_iter().map(|dep| {
+                let reason = PlanReason::Dependency {
+                    of: name.to_string(),
+                    mode: mode.clone(),
+                };
+                (dep, reason)
+            });
+            let host = (host.clone(), PlanReason::Host { of: name.to_string() });
+
+            for (dep, reason) in deps.chain(std::iter::once(host)) {
+                let target = Target {
+                    component: dep,
+                    service: None,
+                };
+
+                self.visit_start(workspace, &target, reason, options, status, visited)?;
+            }
+
+            return Ok(());
+        }
+        let running = status.is_service_running(name, service);
+        let skip = running && !options.force;
+
+        // A running component already has its dependencies satisfied
+        if !skip {
+            let mut deps = get_deps(&component.config, mode);
+            deps.sort();
+
+            for dep in deps {
+                self.visit_start(
+                    workspace,
+                    &Target {
+                        component: dep,
+                        service: None,
+                    },
+                    PlanReason::Dependency {
+                        of: name.to_string(),
+                        mode: mode.clone(),
+                    },
+                    options,
+                    status,
+                    visited,
+                )?;
+            }
+        }
+
+        self.steps.push(PlanStep {
+            component: name.to_string(),
+            service: target.service.clone(),
+            action: PlanAction::Start,
+            reason,
+            running,
+            skip,
+            command: component
+                .compose_command(&PlanAction::Start.compose_command(target.service.as_ref())),
+            env: plan_env(component),
+        });
+
+        Ok(())
+    }
+
+    pub fn execute(
+        &self,
+        workspace: &Workspace,
+        options: &GlobalOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut state = WorkspaceState::load(workspace)?;
+
+        if self.starts_anything() {
+            ensure_resources(workspace, options)?;
+        }
+
+        for step in self.steps.iter().filter(|step| !step.skip) {
+            let component = find_component(workspace, &step.component)?;
+
+            let (before, after) = match step.action {
+                PlanAction::Stop => (HookEvent::BeforeStop, HookEvent::AfterStop),
+                _ => (HookEvent::BeforeStart, HookEvent::AfterStart),
+            };
+
+            component.run_hooks(workspace, before, options)?;
+
+            let compose_command = step.action.compose_command(step.service.as_ref());
+            component.exec_compose(workspace, &compose_command, options, true)?;
+
+            component.run_hooks(workspace, after, options)?;
+
+            if options.dry_run {
+                continue;
+            }
+
+            match (&step.action, &step.reason) {
+                // Other services of the component keep running
+                (PlanAction::Stop, _) if step.service.is_some() => {}
+                (PlanAction::Stop, _) => state.remove(&step.component),
+                (_, PlanReason::Dependency { mode, .. }) => state.record_start(component, mode),
+                (_, PlanReason::Host { .. }) => state.record_start(
+                    component,
+                    options.mode.as_ref().unwrap_or(&Mode::Default),
+                ),
+                (_, PlanReason::Changed) => {
+                    let mode = state
+                        .components
+                        .get(&step.component)
+                        .map(|s| s.mode.clone())
+                        .unwrap_or(Mode::Default);
+                    state.record_start(component, &mode);
+                }
+                (_, PlanReason::Requested) => state.record_start(
+                    component,
+                    options.mode.as_ref().unwrap_or(&Mode::Default),
+                ),
+            }
+
+            // Saved after every step so a failure halfway keeps what did start
+            state.save(workspace)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn print(&self, options: &GlobalOptions) -> Result<(), Box<dyn Error>> {
+        if options.json {
+            println!("{}", serde_json::to_string_pretty(self)?);
+            return Ok(());
+        }
+
+        if self.steps.is_empty() && self.networks.is_empty() && self.volumes.is_empty() {
+            println!("Nothing to do");
+            return Ok(());
+        }
+
+        for (kind, name) in self
+            .networks
+            .iter()
+            .map(|name| ("network", name))
+            .chain(self.volumes.iter().map(|name| ("volume", name)))
+        {
+            println!(
+                "{} {} {}",
+                "create".bright_cyan(),
+                kind,
+                name.bright_green()
+            );
+        }
+
+        for (i, step) in self.steps.iter().enumerate() {
+            let action = match step.action {
+                PlanAction::Start => "start",
+                PlanAction::Stop => "stop",
+                PlanAction::Recreate => "recreate",
+            };
+            let reason = match &step.reason {
+                PlanReason::Requested => "requested".to_string(),
+                PlanReason::Dependency { of, mode } => {
+                    format!("dependency of {} in mode {}", of, mode)
+                }
+                PlanReason::Host { of } => format!("host of {}", of),
+                PlanReason::Changed => "config changed".to_string(),
+            };
+            let state = if step.running { "running" } else { "stopped" };
+
+            let target = match &step.service {
+                Some(service) => format!("{}:{}", step.component, service),
+                None => step.component.clone(),
+            };
+
+            println!(
+                "{}. {} {} ({}, {}{})",
+                i + 1,
+                action.bright_cyan(),
+                target.bright_green(),
+                reason,
+                state,
+                if step.skip { ", skipped" } else { "" }
+            );
+            println!("   $ {}", step.command.join(" "));
+
+            for (k, v) in step.env.iter() {
+                println!("   {}={}", k, v);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn find_component<'a>(
+    workspace: &'a Workspace,
+    name: &str,
+) -> Result<&'a Component, Box<dyn Error>> {
+    workspace.components.get(name).ok_or_else(|| {
+        Box::new(CliError::new(format!("Could not find component \"{}\"", name))) as Box<dyn Error>
+    })
+}
+
+fn plan_env(component: &Component) -> IndexMap<String, String> {
+    let context = component.context.as_ref().unwrap();
+    let mut keys: Vec<String> = PLAN_ENV_KEYS.iter().map(|k| k.to_string()).collect();
+
+    if let Some(vars) = component
+        .template
+        .as_ref()
+        .and_then(|template| template.variables.as_ref())
+    {
+        let mut template_keys: Vec<_> = vars.keys().cloned().collect();
+        template_keys.sort();
+        keys.extend(template_keys);
+    }
+
+    if let Some(vars) = &component.config.variables {
+        let mut component_keys: Vec<_> = vars.keys().cloned().collect();
+        component_keys.sort();
+        keys.extend(component_keys);
+    }
+
+    let mut env = IndexMap::new();
+
+    for key in keys {
+        if let Some(value) = context.get(&key) {
+            env.insert(key, value.clone());
+        }
+    }
+
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        args::{parse_global_options, AppArgs},
+        core::SystemPath,
+        fake_engine::FakeEngine,
+        workspace::create_workspace,
+        workspace_config::WorkspaceConfig,
+    };
+    use clap::Parser;
+
+    const CONFIG: &str = r#"{
+        "name": "shop",
+        "version": "1.0.0",
+        "variables": {},
+        "components": {
+            "node": { "path": "/srv/node", "isTemplate": true },
+            "database": { "path": "/srv/database" },
+            "cache": { "path": "/srv/cache" },
+            "backend": {
+                "path": "/srv/backend",
+                "extends": "node",
+                "dependencies": {
+                    "database": ["default"],
+                    "cache": ["default", "hook"]
+                }
+            },
+            "php": { "path": "/srv/php" },
+            "admin": {
+                "path": "/srv/admin",
+                "hosted_in": "php",
+                "dependencies": { "backend": ["default"] }
+            }
+        }
+    }"#;
+
+    fn workspace(options: &GlobalOptions) -> Workspace {
+        let mut ws = create_workspace(SystemPath::new("/srv"), "/srv");
+        ws.config = Some(serde_json::from_str::<WorkspaceConfig>(CONFIG).unwrap());
+        ws.init(options).unwrap();
+
+        ws
+    }
+
+    fn global_options(args: &[&str]) -> GlobalOptions {
+        let cli = AppArgs::try_parse_from([&["uniform"], args].concat()).unwrap();
+
+        parse_global_options(&cli)
+    }
+
+    fn status(engine: &FakeEngine) -> WorkspaceStatus {
+        let containers = engine.containers.lock().unwrap().clone();
+
+        WorkspaceStatus::from_containers(containers)
+    }
+
+    fn target(name: &str) -> Target {
+        Target {
+            component: name.to_string(),
+            service: None,
+        }
+    }
+
+    fn components(plan: &Plan) -> Vec<&str> {
+        plan.steps.iter().map(|s| s.component.as_str()).collect()
+    }
+
+    #[test]
+    fn starts_dependencies_first() {
+        let options = global_options(&["ps"]);
+        let ws = workspace(&options);
+        let status = status(&FakeEngine::new());
+
+        let plan = Plan::start_with_status(&ws, &[target("backend")], &options, &status).unwrap();
+
+        assert_eq!(components(&plan), ["cache", "database", "backend"]);
+        assert_eq!(
+            plan.steps[0].reason,
+            PlanReason::Dependency {
+                of: "backend".to_string(),
+                mode: Mode::Default
+            }
+        );
+        assert_eq!(plan.steps[2].reason, PlanReason::Requested);
+        assert!(plan
+            .steps
+            .iter()
+            .all(|s| s.action == PlanAction::Start && !s.skip));
+
+        let options = global_options(&["--mode", "hook", "ps"]);
+        let plan = Plan::start_with_status(&ws, &[target("backend")], &options, &status).unwrap();
+
+        assert_eq!(components(&plan), ["cache", "backend"]);
+    }
+
+    #[test]
+    fn skips_running_components_unless_forced() {
+        let options = global_options(&["ps"]);
+        let ws = workspace(&options);
+        let engine = FakeEngine::new();
+        engine.add_container("shop", "database", "db", true);
+        engine.add_container("shop", "backend", "app", true);
+        let status = status(&engine);
+
+        let plan = Plan::start_with_status(&ws, &[target("database")], &options, &status).unwrap();
+
+        assert_eq!(components(&plan), ["database"]);
+        assert!(plan.steps[0].running);
+        assert!(plan.steps[0].skip);
+
+        // A running component has its dependencies satisfied already
+        let plan = Plan::start_with_status(&ws, &[target("backend")], &options, &status).unwrap();
+
+        assert_eq!(components(&plan), ["backend"]);
+        assert!(plan.steps[0].skip);
+
+        let forced = global_options(&["--force", "ps"]);
+        let plan = Plan::start_with_status(&ws, &[target("backend")], &forced, &status).unwrap();
+
+        assert_eq!(components(&plan), ["cache", "database", "backend"]);
+        assert!(!plan.steps[0].skip);
+        assert!(plan.steps[1].running);
+        assert!(!plan.steps[1].skip);
+    }
+
+    #[test]
+    fn hosted_components_start_their_host() {
+        let options = global_options(&["ps"]);
+        let ws = workspace(&options);
+        let status = status(&FakeEngine::new());
+
+        let plan = Plan::start_with_status(&ws, &[target("admin")], &options, &status).unwrap();
+
+        assert_eq!(components(&plan), ["cache", "database", "backend", "php"]);
+        assert_eq!(
+            plan.steps[2].reason,
+            PlanReason::Dependency {
+                of: "admin".to_string(),
+                mode: Mode::Default
+            }
+        );
+        assert_eq!(
+            plan.steps[3].reason,
+            PlanReason::Host {
+                of: "admin".to_string()
+            }
+        );
+
+        // Stopping a hosted component leaves the host alone
+        let plan = Plan::stop_with_status(&ws, &[target("admin")], &status).unwrap();
+
+        assert!(plan.steps.is_empty());
+    }
+
+    #[test]
+    fn stop_skips_stopped_components() {
+        let options = global_options(&["ps"]);
+        let ws = workspace(&options);
+        let engine = FakeEngine::new();
+        engine.add_container("shop", "backend", "app", true);
+        let status = status(&engine);
+
+        let plan =
+            Plan::stop_with_status(&ws, &[target("backend"), target("database")], &status).unwrap();
+
+        assert_eq!(components(&plan), ["backend", "database"]);
+        assert!(plan.steps.iter().all(|s| s.action == PlanAction::Stop));
+        assert!(!plan.steps[0].skip);
+        assert!(plan.steps[1].skip);
+    }
+
+    #[test]
+    fn serializes_steps_for_json_output() {
+        let options = global_options(&["ps"]);
+        let ws = workspace(&options);
+        let status = status(&FakeEngine::new());
+
+        let targets = [
+            Target {
+                component: "backend".to_string(),
+                service: Some("worker".to_string()),
+            },
+            target("admin"),
+        ];
+        let plan = Plan::start_with_status(&ws, &targets, &options, &status).unwrap();
+        let json = serde_json::to_value(&plan).unwrap();
+        let steps = json["steps"].as_array().unwrap();
+
+        assert_eq!(json["networks"], serde_json::json!([]));
+        assert_eq!(json["volumes"], serde_json::json!([]));
+
+        assert_eq!(steps[0]["component"], "cache");
+        assert!(steps[0].get("service").is_none());
+        assert_eq!(steps[0]["action"], "start");
+        assert_eq!(
+            steps[0]["reason"],
+            serde_json::json!({ "type": "dependency", "of": "backend", "mode": "default" })
+        );
+        assert_eq!(steps[0]["running"], false);
+        assert_eq!(steps[0]["skip"], false);
+        assert_eq!(steps[0]["command"][1], "compose");
+        assert_eq!(steps[0]["env"]["COMPOSE_PROJECT_NAME"], "shop-cache");
+
+        assert_eq!(steps[2]["component"], "backend");
+        assert_eq!(steps[2]["service"], "worker");
+        assert_eq!(
+            steps[2]["reason"],
+            serde_json::json!({ "type": "requested" })
+        );
+        assert_eq!(
+            steps[2]["command"].as_array().unwrap().last().unwrap(),
+            "worker"
+        );
+        assert_eq!(steps[2]["env"]["TPL_PATH"], "/srv/node");
+
+        assert_eq!(
+            steps.last().unwrap()["reason"],
+            serde_json::json!({ "type": "host", "of": "admin" })
+        );
+    }
+}
@@ -0,0 +1,986 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::{
+    engine::{Engine, EngineResult},
+    workspace::{Target, Workspace},
+};
+
+pub const PROXY_FORMATS: [&str; 2] = ["nginx", "caddy"];
+
+/// Path of the "start it" action on the proxy error page
+const START_PATH: &str = "/__uniform/start";
+const MAX_HEAD_SIZE: usize = 64 * 1024;
+/// How long a resolved upstream is reused, so the requests for the assets
+/// of a page don't each query the engine
+const UPSTREAM_TTL: Duration = Duration::from_secs(2);
+
+/// Hostname a component is served on and where its requests go
+#[derive(Debug, Clone, Serialize)]
+pub struct Route {
+    pub component: String,
+    pub hostname: String,
+    pub url: String,
+    pub upstream: String,
+    #[serde(skip)]
+    pub target: Target,
+    #[serde(skip)]
+    pub port: u16,
+}
+
+impl Route {
+    /// Where the built-in proxy serves the route when it listens on `port`
+    pub fn proxy_url(&self, port: Option<&str>) -> String {
+        match port {
+            Some(port) if port != "80" => format!("http://{}:{}", self.hostname, port),
+            _ => format!("http://{}", self.hostname),
+        }
+    }
+}
+
+/// Routes of all executable components declaring or inheriting a hostname,
+/// in workspace order
+pub fn collect_routes(workspace: &Workspace) -> Result<Vec<Route>, Box<dyn Error>> {
+    let mut routes = Vec::new();
+
+    for component in workspace.components.values() {
+        if component.config.is_template.unwrap_or(false) {
+            continue;
+        }
+
+        let hostname = match component.get_hostname() {
+            Some(hostname) => hostname,
+            None => continue,
+        };
+
+        routes.push(Route {
+            component: component.name.clone(),
+            url: to_url(&hostname),
+            upstream: component.get_upstream(workspace)?,
+            target: component.get_upstream_target(workspace)?,
+            port: component.get_app_port()?,
+            hostname: strip_scheme(&hostname).to_string(),
+        });
+    }
+
+    Ok(routes)
+}
+
+pub fn to_url(hostname: &str) -> String {
+    if hostname.contains("://") {
+        hostname.to_string()
+    } else {
+        format!("http://{}", hostname)
+    }
+}
+
+fn strip_scheme(hostname: &str) -> &str {
+    match hostname.find("://") {
+        Some(i) => &hostname[i + 3..],
+        None => hostname,
+    }
+}
+
+pub fn render(format: &str, routes: &[Route]) -> Result<String, Box<dyn Error>> {
+    match format {
+        "nginx" => Ok(render_nginx(routes)),
+        "caddy" => Ok(render_caddy(routes)),
+        _ => Err(format!(
+            "Unknown proxy config format \"{}\", expected one of: {}",
+            format,
+            PROXY_FORMATS.join(", ")
+        )
+        .into()),
+    }
+}
+
+// Upstreams are resolved at request time through docker's embedded DNS,
+// so the proxy starts even when some components are stopped
+fn render_nginx(routes: &[Route]) -> String {
+    let mut lines = vec![
+        "# Generated by uniform, do not edit".to_string(),
+        "resolver 127.0.0.11 valid=10s ipv6=off;".to_string(),
+        String::new(),
+        "map $http_upgrade $connection_upgrade {".to_string(),
+        "    default upgrade;".to_string(),
+        "    '' close;".to_string(),
+        "}".to_string(),
+    ];
+
+    for route in routes {
+        lines.push(String::new());
+        lines.push(format!("# {}", route.component));
+        lines.push("server {".to_string());
+        lines.push("    listen 80;".to_string());
+        lines.push(format!("    server_name {};", route.hostname));
+        lines.push(String::new());
+        lines.push("    location / {".to_string());
+        lines.push(format!("        set $upstream {};", route.upstream));
+        lines.push("        proxy_pass http://$upstream;".to_string());
+        lines.push("        proxy_http_version 1.1;".to_string());
+        lines.push("        proxy_set_header Host $host;".to_string());
+        lines.push("        proxy_set_header X-Real-IP $remote_addr;".to_string());
+        lines.push(
+            "        proxy_set_header X-Forwarded-For $proxy_add_x_forwarded_for;".to_string(),
+        );
+        lines.push("        proxy_set_header X-Forwarded-Proto $scheme;".to_string());
+        lines.push("        proxy_set_header Upgrade $http_upgrade;".to_string());
+        lines.push("        proxy_set_header Connection $connection_upgrade;".to_string());
+        lines.push("    }".to_string());
+        lines.push("}".to_string());
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn render_caddy(routes: &[Route]) -> String {
+    let mut lines = vec!["# Generated by uniform, do not edit".to_string()];
+
+    for route in routes {
+        lines.push(String::new());
+        lines.push(format!("# {}", route.component));
+        lines.push(format!("http://{} {{", route.hostname));
+        lines.push(format!("    reverse_proxy {}", route.upstream));
+        lines.push("}".to_string());
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Development reverse proxy routing requests by their `Host:` header to
+/// component containers. Every request on a kept-alive connection is routed
+/// on its own, upgraded connections such as WebSockets are piped through.
+pub struct ProxyServer {
+    workspace_name: String,
+    base_domain: Option<String>,
+    network: Option<String>,
+    aliases: HashMap<String, String>,
+    pub routes: Vec<Route>,
+    engine: Arc<dyn Engine>,
+    upstreams: Mutex<HashMap<String, (Instant, Option<String>)>>,
+}
+
+impl ProxyServer {
+    pub fn new(
+        workspace: &Workspace,
+        engine: Arc<dyn Engine>,
+    ) -> Result<ProxyServer, Box<dyn Error>> {
+        Ok(ProxyServer {
+            workspace_name: workspace.config.as_ref().unwrap().name.clone(),
+            base_domain: workspace.context.get("BASE_DOMAIN").cloned(),
+            network: workspace.context.get("NETWORK").cloned(),
+            aliases: workspace.aliases.clone(),
+            routes: collect_routes(workspace)?,
+            engine,
+            upstreams: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub async fn run(self: Arc<Self>, listen: &str) -> EngineResult<()> {
+        let listener = TcpListener::bind(listen).await?;
+
+        loop {
+            let (client, peer) = listener.accept().await?;
+            let server = self.clone();
+
+            tokio::spawn(async move {
+                if let Err(err) = server.handle(client, peer).await {
+                    eprintln!("{} {}", "Proxy error:".bright_red(), err);
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, client: TcpStream, peer: SocketAddr) -> EngineResult<()> {
+        let mut client = Connection::new(client);
+
+        while let Some(head) = client.read_head().await? {
+            let request = RequestHead::parse(&head);
+
+            if !self.serve(&mut client, request, peer).await? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Answers one request, returns whether the connection can take the next one
+    async fn serve(
+        &self,
+        client: &mut Connection,
+        mut request: RequestHead,
+        peer: SocketAddr,
+    ) -> EngineResult<bool> {
+        let route = match self.find_route(&request.host) {
+            Some(route) => route,
+            None => {
+                let body = self.not_found_page(&request.host);
+                respond(&mut client.stream, "404 Not Found", &body).await?;
+                return Ok(false);
+            }
+        };
+
+        let upstream = match self.cached_upstream(route).await {
+            Ok(upstream) => upstream,
+            Err(err) => {
+                let body = failed_page(route, &err.to_string());
+                respond(&mut client.stream, "502 Bad Gateway", &body).await?;
+                return Ok(false);
+            }
+        };
+        let connected = match &upstream {
+            Some(upstream) => Some(TcpStream::connect(upstream).await),
+            None => None,
+        };
+
+        let server = match connected {
+            Some(Ok(server)) => server,
+            // The start action is only taken over while nothing serves the
+            // route, a running app gets that path like any other
+            _ if request.method == "POST" && request.path == START_PATH => {
+                self.start(client, route, &request).await?;
+                return Ok(false);
+            }
+            None => {
+                respond(&mut client.stream, "502 Bad Gateway", &stopped_page(route)).await?;
+                return Ok(false);
+            }
+            Some(Err(err)) => {
+                self.upstreams.lock().unwrap().remove(&route.component);
+                let upstream = upstream.unwrap_or_default();
+                let body = failed_page(route, &format!("{} is unreachable: {}", upstream, err));
+                respond(&mut client.stream, "502 Bad Gateway", &body).await?;
+                return Ok(false);
+            }
+        };
+        let mut server = Connection::new(server);
+
+        println!(
+            "{} {}{} -> {}",
+            request.method,
+            request.host,
+            request.path,
+            upstream.unwrap_or_default().bright_blue()
+        );
+
+        // Answered here so the body can be sent along with the head
+        if request.head.contains("Expect", "100-continue") {
+            request.head.remove("Expect");
+            client
+                .stream
+                .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+                .await?;
+        }
+
+        let keep_alive = request.head.keep_alive();
+        let body = request.head.body(Body::Empty)?;
+
+        forward_headers(&mut request, peer);
+        server.stream.write_all(&request.head.to_bytes()).await?;
+        client.copy_body(body, &mut server.stream).await?;
+
+        let (bytes, response) = loop {
+            let bytes = server
+                .read_head()
+                .await?
+                .ok_or("Upstream closed the connection without a response")?;
+            let response = Head::parse(&bytes);
+
+            match response.status() {
+                101 => {
+                    client.stream.write_all(&bytes).await?;
+                    client.pipe(&mut server).await;
+                    return Ok(false);
+                }
+                100..=199 => client.stream.write_all(&bytes).await?,
+                _ => break (bytes, response),
+            }
+        };
+
+        let body = match response.status() {
+            204 | 304 => Body::Empty,
+            _ if request.method == "HEAD" => Body::Empty,
+            _ => response.body(Body::UntilClose)?,
+        };
+
+        client.stream.write_all(&bytes).await?;
+        server.copy_body(body, &mut client.stream).await?;
+
+        Ok(keep_alive && response.keep_alive() && !matches!(body, Body::UntilClose))
+    }
+
+    async fn start(
+        &self,
+        client: &mut Connection,
+        route: &Route,
+        request: &RequestHead,
+    ) -> EngineResult<()> {
+        // Any page could post the form otherwise, browsers always send the
+        // origin along with it
+        if !request.is_same_origin() {
+            let body = html_page("Forbidden", "<h1>Cross-origin start request refused</h1>");
+            return respond(&mut client.stream, "403 Forbidden", &body).await;
+        }
+
+        println!("Starting {} from the proxy", route.component.bright_green());
+
+        let result = self.engine.start_component(&route.component).await;
+        self.upstreams.lock().unwrap().clear();
+
+        match result {
+            Ok(()) => redirect(&mut client.stream, "/").await,
+            Err(err) => {
+                let body = failed_page(route, &err.to_string());
+                respond(&mut client.stream, "500 Internal Server Error", &body).await
+            }
+        }
+    }
+
+    /// Matches full hostnames first, then `<component or alias>.<BASE_DOMAIN>`
+    fn find_route(&self, host: &str) -> Option<&Route> {
+        let host = host.split(':').next().unwrap_or(host).to_lowercase();
+
+        if let Some(route) = self
+            .routes
+            .iter()
+            .find(|r| r.hostname.to_lowercase() == host)
+        {
+            return Some(route);
+        }
+
+        let suffix = format!(".{}", self.base_domain.as_ref()?.to_lowercase());
+        let name = host.strip_suffix(&suffix)?;
+        let name = self.aliases.get(name).map(|s| s.as_str()).unwrap_or(name);
+
+        self.routes.iter().find(|r| r.component == name)
+    }
+
+    /// `find_upstream`, reusing an answer younger than `UPSTREAM_TTL`
+    async fn cached_upstream(&self, route: &Route) -> EngineResult<Option<String>> {
+        let cached = self
+            .upstreams
+            .lock()
+            .unwrap()
+            .get(&route.component)
+            .filter(|(resolved_at, _)| resolved_at.elapsed() < UPSTREAM_TTL)
+            .map(|(_, upstream)| upstream.clone());
+
+        if let Some(upstream) = cached {
+            return Ok(upstream);
+        }
+
+        let upstream = self.find_upstream(route).await?;
+        self.upstreams
+            .lock()
+            .unwrap()
+            .insert(route.component.clone(), (Instant::now(), upstream.clone()));
+
+        Ok(upstream)
+    }
+
+    /// Address of the running container serving the route. A published port
+    /// is preferred, it is reachable from the host on every platform.
+    async fn find_upstream(&self, route: &Route) -> EngineResult<Option<String>> {
+        let containers = self.engine.list_containers(&self.workspace_name).await?;
+
+        let container = containers.iter().find(|container| {
+            container.is_running()
+                && container.component() == Some(route.target.component.as_str())
+                && container.service() == route.target.service.as_deref()
+        });
+
+        let container = match container {
+            Some(container) => container,
+            None => return Ok(None),
+        };
+
+        if let Some(port) = container.published_port(route.port) {
+            return Ok(Some(format!("127.0.0.1:{}", port)));
+        }
+
+        let address = self
+            .engine
+            .container_address(&container.id, self.network.as_deref())
+            .await?;
+
+        Ok(address.map(|ip| format!("{}:{}", ip, route.port)))
+    }
+
+    fn not_found_page(&self, host: &str) -> String {
+        let port = host.split_once(':').map(|(_, port)| port);
+        let links: Vec<String> = self
+            .routes
+            .iter()
+            .map(|route| {
+                format!(
+                    "<li><a href=\"{}\">{}</a></li>",
+                    escape_html(&route.proxy_url(port)),
+                    escape_html(&route.component)
+                )
+            })
+            .collect();
+
+        html_page(
+            "Unknown host",
+            &format!(
+                "<h1>No component is routed on {}</h1><p>Known components:</p><ul>{}</ul>",
+                escape_html(host),
+                links.join("")
+            ),
+        )
+    }
+}
+
+/// Request or status line and headers of an HTTP message
+struct Head {
+    line: String,
+    headers: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy)]
+enum Body {
+    Empty,
+    Length(u64),
+    Chunked,
+    UntilClose,
+}
+
+impl Head {
+    fn parse(bytes: &[u8]) -> Head {
+        let text = String::from_utf8_lossy(bytes);
+        let mut lines = text.trim_end_matches("\r\n").split("\r\n");
+        let line = lines.next().unwrap_or("").to_string();
+
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+
+        Head { line, headers }
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Whether a comma separated header such as `Connection:` lists the token
+    fn contains(&self, name: &str, token: &str) -> bool {
+        self.headers
+            .iter()
+            .filter(|(header, _)| header.eq_ignore_ascii_case(name))
+            .flat_map(|(_, value)| value.split(','))
+            .any(|value| value.trim().eq_ignore_ascii_case(token))
+    }
+
+    fn remove(&mut self, name: &str) {
+        self.headers
+            .retain(|(header, _)| !header.eq_ignore_ascii_case(name));
+    }
+
+    fn status(&self) -> u16 {
+        self.line
+            .split(' ')
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .unwrap_or(0)
+    }
+
+    fn keep_alive(&self) -> bool {
+        if self.contains("Connection", "close") {
+            false
+        } else if self.line.contains("HTTP/1.0") {
+            self.contains("Connection", "keep-alive")
+        } else {
+            true
+        }
+    }
+
+    /// How the body is framed, `default` when there is no framing header
+    fn body(&self, default: Body) -> EngineResult<Body> {
+        if self.contains("Transfer-Encoding", "chunked") {
+            return Ok(Body::Chunked);
+        }
+
+        match self.get("Content-Length") {
+            Some(length) => Ok(Body::Length(
+                length.parse().map_err(|_| "Invalid Content-Length")?,
+            )),
+            None => Ok(default),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut head = self.line.clone();
+
+        for (name, value) in &self.headers {
+            head.push_str(&format!("\r\n{}: {}", name, value));
+        }
+
+        head.push_str("\r\n\r\n");
+        head.into_bytes()
+    }
+}
+
+struct RequestHead {
+    method: String,
+    path: String,
+    host: String,
+    head: Head,
+}
+
+impl RequestHead {
+    fn parse(bytes: &[u8]) -> RequestHead {
+        let head = Head::parse(bytes);
+
+        let mut request_line = head.line.split(' ');
+        let method = request_line.next().unwrap_or("").to_string();
+        let path = request_line.next().unwrap_or("/").to_string();
+        let host = head.get("Host").unwrap_or_default().to_string();
+
+        RequestHead {
+            method,
+            path,
+            host,
+            head,
+        }
+    }
+
+    /// Whether the `Origin:`, or else `Referer:`, is the host the request was sent to
+    fn is_same_origin(&self) -> bool {
+        let origin = match self.head.get("Origin").or_else(|| self.head.get("Referer")) {
+            Some(origin) => strip_scheme(origin),
+            None => return false,
+        };
+        let origin = origin.split('/').next().unwrap_or(origin);
+
+        !self.host.is_empty() && origin.eq_ignore_ascii_case(&self.host)
+    }
+}
+
+/// Client or upstream stream with the bytes read past the current message head
+struct Connection {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Connection {
+        Connection {
+            stream,
+            buffer: Vec::new(),
+        }
+    }
+
+    async fn fill(&mut self) -> EngineResult<usize> {
+        let mut chunk = [0u8; 8192];
+        let read = self.stream.read(&mut chunk).await?;
+
+        self.buffer.extend_from_slice(&chunk[..read]);
+
+        Ok(read)
+    }
+
+    /// Reads up to and including the blank line after the headers, `None`
+    /// if the other side hung up between messages
+    async fn read_head(&mut self) -> EngineResult<Option<Vec<u8>>> {
+        loop {
+            if let Some(end) = find_head_end(&self.buffer) {
+                return Ok(Some(self.buffer.drain(..end + 4).collect()));
+            }
+
+            if self.buffer.len() > MAX_HEAD_SIZE {
+                return Err("Message head is too large".into());
+            }
+
+            if self.fill().await? == 0 {
+                return match self.buffer.is_empty() {
+                    true => Ok(None),
+                    false => Err("Connection closed in the middle of a message head".into()),
+                };
+            }
+        }
+    }
+
+    async fn read_line(&mut self) -> EngineResult<Vec<u8>> {
+        loop {
+            if let Some(end) = self.buffer.windows(2).position(|w| w == b"\r\n") {
+                return Ok(self.buffer.drain(..end + 2).collect());
+            }
+
+            if self.buffer.len() > MAX_HEAD_SIZE {
+                return Err("Chunk line is too long".into());
+            }
+
+            if self.fill().await? == 0 {
+                return Err("Connection closed in the middle of a chunk".into());
+            }
+        }
+    }
+
+    async fn copy_exact(&mut self, mut length: u64, to: &mut TcpStream) -> EngineResult<()> {
+        while length > 0 {
+            if self.buffer.is_empty() && self.fill().await? == 0 {
+                return Err("Connection closed in the middle of a body".into());
+            }
+
+            let count = length.min(self.buffer.len() as u64) as usize;
+            to.write_all(&self.buffer[..count]).await?;
+            self.buffer.drain(..count);
+            length -= count as u64;
+        }
+
+        Ok(())
+    }
+
+    async fn copy_body(&mut self, body: Body, to: &mut TcpStream) -> EngineResult<()> {
+        match body {
+            Body::Empty => Ok(()),
+            Body::Length(length) => self.copy_exact(length, to).await,
+            Body::Chunked => loop {
+                let line = self.read_line().await?;
+                to.write_all(&line).await?;
+
+                let size = String::from_utf8_lossy(&line);
+                let size = size.split(';').next().unwrap_or("").trim();
+                let size = u64::from_str_radix(size, 16).map_err(|_| "Invalid chunk size")?;
+
+                if size == 0 {
+                    // Trailers end with an empty line like the head does
+                    loop {
+                        let line = self.read_line().await?;
+                        to.write_all(&line).await?;
+
+                        if line == b"\r\n" {
+                            return Ok(());
+                        }
+                    }
+                }
+
+                self.copy_exact(size + 2, to).await?;
+            },
+            Body::UntilClose => {
+                to.write_all(&self.buffer).await?;
+                self.buffer.clear();
+                tokio::io::copy(&mut self.stream, to).await?;
+
+                Ok(())
+            }
+        }
+    }
+
+    async fn pipe(&mut self, other: &mut Connection) {
+        // Either side hanging up ends the connection, that is not an error
+        let _ = other.stream.write_all(&self.buffer).await;
+        let _ = self.stream.write_all(&other.buffer).await;
+        let _ = tokio::io::copy_bidirectional(&mut self.stream, &mut other.stream).await;
+    }
+}
+
+// Position right after the last header, before the blank line
+fn find_head_end(buffer: &[u8]) -> Option<usize> {
+    buffer.windows(4).position(|window| window == b"\r\n\r\n")
+}
+
+fn forward_headers(request: &mut RequestHead, peer: SocketAddr) {
+    let headers = &mut request.head.headers;
+
+    headers.push(("X-Forwarded-For".to_string(), peer.ip().to_string()));
+    headers.push(("X-Forwarded-Host".to_string(), request.host.clone()));
+    headers.push(("X-Forwarded-Proto".to_string(), "http".to_string()));
+}
+
+async fn respond(client: &mut TcpStream, status: &str, body: &str) -> EngineResult<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+
+    client.write_all(response.as_bytes()).await?;
+
+    Ok(())
+}
+
+async fn redirect(client: &mut TcpStream, location: &str) -> EngineResult<()> {
+    let response = format!(
+        "HTTP/1.1 303 See Other\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        location
+    );
+
+    client.write_all(response.as_bytes()).await?;
+
+    Ok(())
+}
+
+fn stopped_page(route: &Route) -> String {
+    let hosted = if route.target.component != route.component {
+        format!(
+            " It runs inside <b>{}</b>, which is stopped.",
+            escape_html(&route.target.component)
+        )
+    } else {
+        String::new()
+    };
+
+    html_page(
+        &format!("{} is not running", route.component),
+        &format!(
+            "<h1>{} is not running</h1><p>Nothing serves {}.{}</p>\
+             <form method=\"post\" action=\"{}\"><button>Start it</button></form>",
+            escape_html(&route.component),
+            escape_html(&route.hostname),
+            hosted,
+            START_PATH
+        ),
+    )
+}
+
+fn failed_page(route: &Route, error: &str) -> String {
+    html_page(
+        &format!("{} failed", route.component),
+        &format!(
+            "<h1>{} is not reachable</h1><pre>{}</pre>\
+             <form method=\"post\" action=\"{}\"><button>Start it</button></form>",
+            escape_html(&route.component),
+            escape_html(error),
+            START_PATH
+        ),
+    )
+}
+
+fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>{} - uniform</title>\
+         <style>body{{font-family:sans-serif;max-width:40em;margin:4em auto;color:#333}}\
+         button{{font-size:1em;padding:.5em 1.5em;cursor:pointer}}pre{{white-space:pre-wrap}}</style>\
+         </head><body>{}</body></html>",
+        escape_html(title),
+        body
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_engine::FakeEngine;
+    use std::sync::atomic::Ordering;
+
+    fn route(component: &str, hostname: &str) -> Route {
+        Route {
+            component: component.to_string(),
+            hostname: hostname.to_string(),
+            url: to_url(hostname),
+            upstream: format!("{}:80", component),
+            target: Target {
+                component: component.to_string(),
+                service: Some("app".to_string()),
+            },
+            port: 80,
+        }
+    }
+
+    fn proxy(engine: Arc<FakeEngine>, routes: Vec<Route>) -> Arc<ProxyServer> {
+        Arc::new(ProxyServer {
+            workspace_name: "test".to_string(),
+            base_domain: Some("test".to_string()),
+            network: None,
+            aliases: HashMap::new(),
+            routes,
+            engine,
+            upstreams: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Upstream answering every request with the name it was routed to
+    async fn upstream(name: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut connection = Connection::new(stream);
+
+                while let Ok(Some(head)) = connection.read_head().await {
+                    let head = Head::parse(&head);
+                    let body = format!(
+                        "{} {}",
+                        name,
+                        head.get("X-Forwarded-Host").unwrap_or_default()
+                    );
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+
+                    if connection
+                        .stream
+                        .write_all(response.as_bytes())
+                        .await
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            }
+        });
+
+        port
+    }
+
+    /// Proxies one client connection, returns the client end
+    async fn connect(server: Arc<ProxyServer>) -> Connection {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let (stream, peer) = listener.accept().await.unwrap();
+
+        tokio::spawn(async move { server.handle(stream, peer).await });
+
+        Connection::new(client)
+    }
+
+    async fn response(client: &mut Connection) -> (u16, String) {
+        let head = Head::parse(&client.read_head().await.unwrap().unwrap());
+        let length = head.get("Content-Length").unwrap().parse().unwrap();
+
+        let mut body = Vec::new();
+        while body.len() < length {
+            if client.buffer.is_empty() {
+                client.fill().await.unwrap();
+            }
+            let count = (length - body.len()).min(client.buffer.len());
+            body.extend(client.buffer.drain(..count));
+        }
+
+        (head.status(), String::from_utf8(body).unwrap())
+    }
+
+    fn add_running(engine: &FakeEngine, component: &str, port: u16) {
+        engine.add_container("test", component, "app", true);
+        engine.containers.lock().unwrap().last_mut().unwrap().ports =
+            format!("127.0.0.1:{}->80/tcp", port);
+    }
+
+    #[tokio::test]
+    async fn routes_every_request_on_a_connection() {
+        let engine = Arc::new(FakeEngine::new());
+        add_running(&engine, "first", upstream("first").await);
+        add_running(&engine, "second", upstream("second").await);
+
+        let server = proxy(
+            engine.clone(),
+            vec![route("first", "first.test"), route("second", "second.test")],
+        );
+        let mut client = connect(server).await;
+
+        client
+            .stream
+            .write_all(
+                b"GET / HTTP/1.1\r\nHost: first.test\r\n\r\n\
+                  POST / HTTP/1.1\r\nHost: second.test\r\nContent-Length: 2\r\n\r\nhi\
+                  GET / HTTP/1.1\r\nHost: first.test\r\n\r\n",
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response(&mut client).await,
+            (200, "first first.test".to_string())
+        );
+        assert_eq!(
+            response(&mut client).await,
+            (200, "second second.test".to_string())
+        );
+        assert_eq!(
+            response(&mut client).await,
+            (200, "first first.test".to_string())
+        );
+        // Upstreams are resolved once per route while the cache is fresh
+        assert_eq!(engine.queries.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn engine_errors_get_a_bad_gateway_page() {
+        let engine = Arc::new(FakeEngine::new());
+        *engine.error.lock().unwrap() = Some("docker is not running".to_string());
+        let server = proxy(engine, vec![route("app", "app.test")]);
+
+        let mut client = connect(server).await;
+        client
+            .stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: app.test\r\n\r\n")
+            .await
+            .unwrap();
+
+        let (status, body) = response(&mut client).await;
+        assert_eq!(status, 502);
+        assert!(body.contains("docker is not running"));
+    }
+
+    #[tokio::test]
+    async fn start_action_requires_same_origin() {
+        let engine = Arc::new(FakeEngine::new());
+        engine.add_container("test", "app", "app", false);
+        let server = proxy(engine.clone(), vec![route("app", "app.test")]);
+
+        let mut client = connect(server.clone()).await;
+        client
+            .stream
+            .write_all(
+                b"POST /__uniform/start HTTP/1.1\r\nHost: app.test\r\n\
+                  Origin: http://evil.example\r\nContent-Length: 0\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response(&mut client).await.0, 403);
+        assert!(!engine.containers.lock().unwrap()[0].is_running());
+
+        let mut client = connect(server).await;
+        client
+            .stream
+            .write_all(
+                b"POST /__uniform/start HTTP/1.1\r\nHost: app.test\r\n\
+                  Origin: http://app.test\r\nContent-Length: 0\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        assert_eq!(response(&mut client).await.0, 303);
+        assert!(engine.containers.lock().unwrap()[0].is_running());
+    }
+
+    #[test]
+    fn find_route_ignores_case() {
+        let server = proxy(
+            Arc::new(FakeEngine::new()),
+            vec![route("app", "App.Test"), route("api", "api.example")],
+        );
+
+        assert_eq!(server.find_route("app.test:8880").unwrap().component, "app");
+        assert_eq!(server.find_route("API.TEST").unwrap().component, "api");
+        assert!(server.find_route("other.test").is_none());
+    }
+}
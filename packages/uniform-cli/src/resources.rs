@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::process::Command;
+
+use colored::Colorize;
+use serde::Deserialize;
+
+use crate::{
+    args::CliError,
+    core::{docker_program, subst_vars, GlobalOptions},
+    engine::{deserialize_labels, COMPONENT_LABEL, WORKSPACE_LABEL},
+    workspace::Workspace,
+    workspace_config::{NetworkConfig, VolumeConfig},
+};
+
+/// Label docker compose puts on volumes it creates for a project
+const PROJECT_LABEL: &str = "com.docker.compose.project";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct NetworkInfo {
+    pub name: String,
+    pub driver: String,
+    #[serde(deserialize_with = "deserialize_labels")]
+    pub labels: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct VolumeInfo {
+    pub name: String,
+    pub driver: String,
+    #[serde(deserialize_with = "deserialize_labels")]
+    pub labels: HashMap<String, String>,
+}
+
+/// Declared networks with resolved names. Without a `networks` section the
+/// `NETWORK` variable compose files use as external network is managed.
+pub fn declared_networks(workspace: &Workspace) -> Vec<(String, NetworkConfig)> {
+    let config = workspace.config.as_ref().unwrap();
+
+    if config.networks.is_empty() {
+        return workspace
+            .context
+            .get("NETWORK")
+            .map(|network| vec![(network.clone(), NetworkConfig::default())])
+            .unwrap_or_default();
+    }
+
+    config
+        .networks
+        .iter()
+        .map(|(name, network)| (subst_vars(name, &workspace.context), network.clone()))
+        .collect()
+}
+
+pub fn declared_volumes(workspace: &Workspace) -> Vec<(String, VolumeConfig)> {
+    workspace
+        .config
+        .as_ref()
+        .unwrap()
+        .volumes
+        .iter()
+        .map(|(name, volume)| (subst_vars(name, &workspace.context), volume.clone()))
+        .collect()
+}
+
+/// Declared networks and volumes that do not exist yet, with resolved names
+#[derive(Debug, Clone, Default)]
+pub struct MissingResources {
+    pub networks: Vec<(String, NetworkConfig)>,
+    pub volumes: Vec<(String, VolumeConfig)>,
+}
+
+pub fn missing_resources(workspace: &Workspace) -> Result<MissingResources, Box<dyn Error>> {
+    let mut networks = declared_networks(workspace);
+    let mut volumes = declared_volumes(workspace);
+
+    if !networks.is_empty() {
+        let existing = docker(&["network", "ls", "--format", "{{.Name}}"])?;
+        networks.retain(|(name, _)| !existing.lines().any(|line| line == name));
+    }
+
+    if !volumes.is_empty() {
+        let existing = docker(&["volume", "ls", "--format", "{{.Name}}"])?;
+        volumes.retain(|(name, _)| !existing.lines().any(|line| line == name));
+    }
+
+    Ok(MissingResources { networks, volumes })
+}
+
+/// Creates declared networks and volumes that do not exist yet. A dry run
+/// creates nothing, its plan lists them instead.
+pub fn ensure_resources(
+    workspace: &Workspace,
+    options: &GlobalOptions,
+) -> Result<(), Box<dyn Error>> {
+    if options.dry_run {
+        return Ok(());
+    }
+
+    let MissingResources { networks, volumes } = missing_resources(workspace)?;
+    let label = workspace_label(workspace);
+
+    for (name, network) in networks {
+        let mut args = vec!["network", "create", "--label", &label];
+        if let Some(driver) = &network.driver {
+            args.extend(["--driver", driver]);
+        }
+        args.push(&name);
+
+        docker(&args)?;
+        println!("Created network {}", name.bright_green());
+    }
+
+    for (name, volume) in volumes {
+        let component_label = volume
+            .component
+            .as_ref()
+            .map(|component| format!("{}={}", COMPONENT_LABEL, component));
+
+        let mut args = vec!["volume", "create", "--label", &label];
+        if let Some(component_label) = &component_label {
+            args.extend(["--label", component_label]);
+        }
+        if let Some(driver) = &volume.driver {
+            args.extend(["--driver", driver]);
+        }
+        args.push(&name);
+
+        docker(&args)?;
+        println!("Created volume {}", name.bright_green());
+    }
+
+    Ok(())
+}
+
+/// Networks that are declared or were created for the workspace
+pub fn list_networks(workspace: &Workspace) -> Result<Vec<NetworkInfo>, Box<dyn Error>> {
+    let workspace_name = &workspace.config.as_ref().unwrap().name;
+    let declared = declared_networks(workspace);
+
+    let networks = list_json::<NetworkInfo>(&["network", "ls"])?
+        .into_iter()
+        .filter(|network| {
+            network.labels.get(WORKSPACE_LABEL) == Some(workspace_name)
+                || declared.iter().any(|(name, _)| *name == network.name)
+        })
+        .collect();
+
+    Ok(networks)
+}
+
+/// Declared volumes and volumes compose created for workspace components
+pub fn list_volumes(workspace: &Workspace) -> Result<Vec<VolumeInfo>, Box<dyn Error>> {
+    let workspace_name = &workspace.config.as_ref().unwrap().name;
+    let declared = declared_volumes(workspace);
+
+    let volumes = list_json::<VolumeInfo>(&["volume", "ls"])?
+        .into_iter()
+        .filter(|volume| {
+            volume.labels.get(WORKSPACE_LABEL) == Some(workspace_name)
+                || declared.iter().any(|(name, _)| *name == volume.name)
+                || volume_component(workspace, volume).is_some()
+        })
+        .collect();
+
+    Ok(volumes)
+}
+
+/// Component a volume belongs to: as declared, as labelled by uniform,
+/// or by the compose project that created it
+pub fn volume_component(workspace: &Workspace, volume: &VolumeInfo) -> Option<String> {
+    let declared = declared_volumes(workspace)
+        .into_iter()
+        .find(|(name, _)| *name == volume.name)
+        .and_then(|(_, config)| config.component);
+
+    if declared.is_some() {
+        return declared;
+    }
+
+    if let Some(component) = volume.labels.get(COMPONENT_LABEL) {
+        return Some(component.clone());
+    }
+
+    let project = volume.labels.get(PROJECT_LABEL)?;
+
+    workspace
+        .components
+        .values()
+        .find(|component| {
+            component
+                .context
+                .as_ref()
+                .and_then(|ctx| ctx.get("COMPOSE_PROJECT_NAME"))
+                == Some(project)
+        })
+        .map(|component| component.name.clone())
+}
+
+pub fn remove_network(name: &str) -> Result<(), Box<dyn Error>> {
+    docker(&["network", "rm", name])?;
+
+    Ok(())
+}
+
+pub fn remove_volume(name: &str) -> Result<(), Box<dyn Error>> {
+    docker(&["volume", "rm", name])?;
+
+    Ok(())
+}
+
+fn workspace_label(workspace: &Workspace) -> String {
+    format!(
+        "{}={}",
+        WORKSPACE_LABEL,
+        workspace.config.as_ref().unwrap().name
+    )
+}
+
+fn list_json<T: for<'de> Deserialize<'de>>(args: &[&str]) -> Result<Vec<T>, Box<dyn Error>> {
+    let mut args = args.to_vec();
+    args.extend(["--format", "{{json .}}"]);
+
+    let stdout = docker(&args)?;
+    let mut items = Vec::new();
+
+    for line in stdout.lines().filter(|line| !line.trim().is_empty()) {
+        items.push(serde_json::from_str(line)?);
+    }
+
+    Ok(items)
+}
+
+/// Runs the docker CLI, failing with its stderr
+pub fn docker(args: &[&str]) -> Result<String, Box<dyn Error>> {
+    let output = Command::new(docker_program()).args(args).output()?;
+
+    if !output.status.success() {
+        return Err(Box::new(CliError::new(
+            String::from_utf8_lossy(&output.stderr).trim(),
+        )));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        args::{parse_global_options, AppArgs},
+        core::SystemPath,
+        workspace::create_workspace,
+    };
+    use clap::Parser;
+
+    fn workspace(resources: &str) -> Workspace {
+        let config = format!(
+            r#"{{
+                "name": "shop",
+                "version": "1.0.0",
+                "variables": {{ "NETWORK": "shop-net" }},
+                "components": {{
+                    "database": {{ "path": "/srv/database" }},
+                    "search": {{ "path": "/srv/search" }}
+                }}
+                {}
+            }}"#,
+            resources
+        );
+        let cli = AppArgs::try_parse_from(["uniform", "ps"]).unwrap();
+
+        let mut ws = create_workspace(SystemPath::new("/srv"), "/srv");
+        ws.config = Some(serde_json::from_str(&config).unwrap());
+        ws.init(&parse_global_options(&cli)).unwrap();
+
+        ws
+    }
+
+    fn volume(name: &str, labels: &[(&str, &str)]) -> VolumeInfo {
+        VolumeInfo {
+            name: name.to_string(),
+            driver: "local".to_string(),
+            labels: labels
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    fn names<T>(resources: Vec<(String, T)>) -> Vec<String> {
+        resources.into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn networks_fall_back_to_the_network_variable() {
+        assert_eq!(names(declared_networks(&workspace(""))), ["shop-net"]);
+
+        let ws = workspace(
+            r#", "networks": { "${WORKSPACE_NAME}-front": {}, "back": { "driver": "overlay" } }"#,
+        );
+        let networks = declared_networks(&ws);
+
+        assert_eq!(networks[0].0, "shop-front");
+        assert_eq!(networks[1].0, "back");
+        assert_eq!(networks[1].1.driver.as_deref(), Some("overlay"));
+    }
+
+    #[test]
+    fn volume_component_prefers_declared_then_label_then_project() {
+        let ws =
+            workspace(r#", "volumes": { "${WORKSPACE_NAME}-data": { "component": "database" } }"#);
+
+        // Declared component wins over a label saying otherwise
+        let declared = volume("shop-data", &[(COMPONENT_LABEL, "search")]);
+        assert_eq!(
+            volume_component(&ws, &declared).as_deref(),
+            Some("database")
+        );
+
+        let labelled = volume(
+            "search-index",
+            &[
+                (COMPONENT_LABEL, "search"),
+                (PROJECT_LABEL, "shop-database"),
+            ],
+        );
+        assert_eq!(volume_component(&ws, &labelled).as_deref(), Some("search"));
+
+        let compose = volume("shop-database_pgdata", &[(PROJECT_LABEL, "shop-database")]);
+        assert_eq!(volume_component(&ws, &compose).as_deref(), Some("database"));
+
+        let unrelated = volume("other_data", &[(PROJECT_LABEL, "other-database")]);
+        assert_eq!(volume_component(&ws, &unrelated), None);
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use colored::Colorize;
+use indexmap::IndexMap;
+
+use crate::{
+    args::{parse_global_options, AppArgs, CliError, CommandType},
+    core::{exec_shell_piped, shell_quote, subst_known_vars, subst_vars, GlobalOptions},
+    workspace::Workspace,
+    workspace_config::ScriptStep,
+};
+
+/// How long a single step of a script took
+#[derive(Debug, Clone)]
+pub struct StepTiming {
+    pub label: String,
+    pub duration: Duration,
+    pub success: bool,
+}
+
+/// Runs workspace scripts step by step, stopping at the first failure
+pub struct ScriptRunner<'a> {
+    workspace: &'a Workspace,
+    options: &'a GlobalOptions,
+    pub timings: Vec<StepTiming>,
+    stack: Vec<String>,
+}
+
+impl<'a> ScriptRunner<'a> {
+    pub fn new(workspace: &'a Workspace, options: &'a GlobalOptions) -> ScriptRunner<'a> {
+        ScriptRunner {
+            workspace,
+            options,
+            timings: Vec::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    pub fn run(&mut self, name: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let scripts = &self.workspace.config.as_ref().unwrap().scripts;
+
+        let steps = scripts.get(name).ok_or_else(|| {
+            CliError::new(format!(
+                "Unknown script \"{}\", available: {}",
+                name,
+                scripts.keys().cloned().collect::<Vec<_>>().join(", ")
+            ))
+        })?;
+
+        if self.stack.iter().any(|script| script == name) {
+            return Err(Box::new(CliError::new(format!(
+                "Script \"{}\" calls itself: {} > {}",
+                name,
+                self.stack.join(" > "),
+                name
+            ))));
+        }
+
+        self.stack.push(name.to_string());
+
+        let vars = self.variables(args);
+
+        for (index, step) in steps.iter().enumerate() {
+            let label = format!("{} #{}", self.stack.join(" > "), index + 1);
+
+            // Nested scripts report timings of their own steps
+            if let ScriptStep::Script { script } = step {
+                let line = subst_vars(script, &vars);
+                let mut words = shell_words::split(&line)?.into_iter();
+                let nested = words.next().ok_or_else(|| {
+                    CliError::new(format!("Step {} has an empty script name", label))
+                })?;
+
+                println!("{} run {}", format!("[{}]", label).bright_cyan(), line);
+                self.run(&nested, &words.collect::<Vec<_>>())?;
+                continue;
+            }
+
+            let started = Instant::now();
+            let result = self.run_step(&label, step, &vars);
+
+            self.timings.push(StepTiming {
+                label: label.clone(),
+                duration: started.elapsed(),
+                success: result.is_ok(),
+            });
+
+            if let Err(err) = result {
+                return Err(Box::new(CliError::new(format!(
+                    "Step {} failed: {}",
+                    label, err
+                ))));
+            }
+        }
+
+        self.stack.pop();
+
+        Ok(())
+    }
+
+    fn run_step(
+        &self,
+        label: &str,
+        step: &ScriptStep,
+        vars: &IndexMap<String, String>,
+    ) -> Result<(), Box<dyn Error>> {
+        match step {
+            ScriptStep::Shell(command) => {
+                let command = subst_known_vars(command, vars);
+                println!("{} $ {}", format!("[{}]", label).bright_cyan(), command);
+
+                if self.options.dry_run {
+                    return Ok(());
+                }
+
+                let cwd = self
+                    .workspace
+                    .context
+                    .get("WORKSPACE_PATH")
+                    .cloned()
+                    .unwrap_or_else(|| self.workspace.cwd.clone());
+                let env: HashMap<String, String> = self
+                    .workspace
+                    .context
+                    .iter()
+                    .map(|(k, v)| (k.clone(), v.clone()))
+                    .collect();
+
+                exec_shell_piped(&command, &cwd, &env, None, None)
+            }
+            ScriptStep::Uniform { uniform } => {
+                let line = subst_vars(uniform, vars);
+                println!("{} uniform {}", format!("[{}]", label).bright_cyan(), line);
+
+                let words = shell_words::split(&line)?;
+                let cli =
+                    AppArgs::try_parse_from(std::iter::once("uniform".to_string()).chain(words))
+                        .map_err(|err| CliError::new(err.to_string()))?;
+
+                if let CommandType::Run(_) = cli.command {
+                    return Err(Box::new(CliError::new(
+                        "Use a script step to run another script",
+                    )));
+                }
+
+                let mut options = parse_global_options(&cli);
+                options.debug |= self.options.debug;
+                options.force |= self.options.force;
+                options.dry_run |= self.options.dry_run;
+                options.no_tty |= self.options.no_tty;
+                options.json |= self.options.json;
+                options.mode = options.mode.or_else(|| self.options.mode.clone());
+                options.tag = options.tag.or_else(|| self.options.tag.clone());
+
+                if let Some(out) = cli.command.execute(&options)? {
+                    println!("{}", out);
+                }
+
+                Ok(())
+            }
+            ScriptStep::Script { .. } => unreachable!("nested scripts are run by the caller"),
+        }
+    }
+
+    /// Workspace variables plus `${1}`.. and `${@}` for the arguments
+    fn variables(&self, args: &[String]) -> IndexMap<String, String> {
+        let mut vars = self.workspace.context.clone();
+
+        for (index, arg) in args.iter().enumerate() {
+            vars.insert((index + 1).to_string(), shell_quote(arg));
+        }
+
+        vars.insert(
+            "@".to_string(),
+            args.iter()
+                .map(|arg| shell_quote(arg))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+
+        vars
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::TEST_DOCKER,
+        settings::{Settings, TEST_HOME},
+        workspace::create_workspace,
+    };
+    use std::fs;
+
+    #[test]
+    #[cfg(unix)]
+    fn failing_exec_step_stops_the_script() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = std::env::temp_dir().join(format!("uniform-script-{}", std::process::id()));
+        let bin = root.join("bin");
+        let app = root.join("app");
+        fs::create_dir_all(&bin).unwrap();
+        fs::create_dir_all(&app).unwrap();
+
+        // docker whose exec fails while everything else succeeds
+        let docker = bin.join("docker");
+        fs::write(
+            &docker,
+            "#!/bin/sh\ncase \"$*\" in *\" exec \"*) exit 3 ;; esac\nexit 0\n",
+        )
+        .unwrap();
+        fs::set_permissions(&docker, fs::Permissions::from_mode(0o755)).unwrap();
+
+        fs::write(
+            app.join("docker-compose.yml"),
+            "services:\n  app:\n    image: alpine\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("uniform.json"),
+            r#"{
+                "name": "script-test",
+                "version": "1.0.0",
+                "variables": {
+                    "USER_ID": "1000",
+                    "GROUP_ID": "1000",
+                    "HOME_PATH": "${WORKSPACE_PATH}/.uniform"
+                },
+                "scripts": {
+                    "seed": [
+                        { "uniform": "exec app -- npm run seed" },
+                        "touch ${WORKSPACE_PATH}/reached"
+                    ]
+                },
+                "components": {
+                    "app": { "path": "${WORKSPACE_PATH}/app" }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        TEST_HOME.with(|home| *home.borrow_mut() = Some(root.clone()));
+        TEST_DOCKER.with(|program| *program.borrow_mut() = Some(docker.display().to_string()));
+
+        let mut settings = Settings::new();
+        settings.active_project = "script-test".to_string();
+        settings
+            .projects
+            .insert("script-test".to_string(), root.display().to_string());
+        settings.save().unwrap();
+
+        let cli = AppArgs::try_parse_from(["uniform", "--no-tty", "ps"]).unwrap();
+        let options = parse_global_options(&cli);
+
+        let mut ws = create_workspace(
+            settings.get_active_project_path().unwrap(),
+            &root.display().to_string(),
+        );
+        ws.load().unwrap();
+        ws.init(&options).unwrap();
+
+        let mut runner = ScriptRunner::new(&ws, &options);
+        let result = runner.run("seed", &[]);
+        let reached = root.join("reached").exists();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Step seed #1 failed"));
+        assert!(!reached);
+        assert_eq!(runner.timings.len(), 1);
+        assert!(!runner.timings[0].success);
+    }
+}
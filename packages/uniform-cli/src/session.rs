@@ -0,0 +1,313 @@
+use std::error::Error;
+use std::fs::{self, create_dir_all};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    args::CliError,
+    component::Component,
+    core::{validate_name, GlobalOptions, Mode},
+    state::WorkspaceState,
+    status::WorkspaceStatus,
+    workspace::Workspace,
+};
+
+/// Component that was running when a session was saved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionComponent {
+    pub mode: Mode,
+    /// Checked out branch, for components with their own git repository
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
+
+/// Named set of running components kept in ${HOME_PATH}/sessions/<name>.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub name: String,
+    pub saved_at: u64,
+    pub components: IndexMap<String, SessionComponent>,
+}
+
+impl Session {
+    pub fn dir(workspace: &Workspace) -> PathBuf {
+        workspace.home_path().join("sessions")
+    }
+
+    pub fn get_file_path(workspace: &Workspace, name: &str) -> PathBuf {
+        Session::dir(workspace).join(format!("{}.json", name))
+    }
+
+    /// Snapshot of the components running right now
+    pub fn capture(
+        workspace: &Workspace,
+        name: &str,
+        options: &GlobalOptions,
+    ) -> Result<Session, Box<dyn Error>> {
+        let state = WorkspaceState::load(workspace)?;
+        let status = WorkspaceStatus::load(workspace, options)?;
+        let mut components = IndexMap::new();
+
+        for component_name in workspace.get_executable_component_names() {
+            let component = workspace.components.get(&component_name).unwrap();
+
+            // Hosted components run as long as their host does
+            let runner = component.config.hosted_in.as_ref().unwrap_or(&component_name);
+
+            if !status.is_running(runner) {
+                continue;
+            }
+
+            let mode = state
+                .components
+                .get(&component_name)
+                .map(|s| s.mode.clone())
+                .unwrap_or(Mode::Default);
+
+            components.insert(
+                component_name,
+                SessionComponent {
+                    mode,
+                    branch: git_branch(component),
+                },
+            );
+        }
+
+        let saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        Ok(Session {
+            name: name.to_string(),
+            saved_at,
+            components,
+        })
+    }
+
+    pub fn load(workspace: &Workspace, name: &str) -> Result<Session, Box<dyn Error>> {
+        validate_name("session", name)?;
+
+        let path = Session::get_file_path(workspace, name);
+
+        if !path.exists() {
+            return Err(Box::new(CliError::new(format!(
+                "Session \"{}\" not found, see `uniform session ls`",
+                name
+            ))));
+        }
+
+        let content = fs::read_to_string(&path)?;
+
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save(&self, workspace: &Workspace) -> Result<(), Box<dyn Error>> {
+        validate_name("session", &self.name)?;
+
+        let path = Session::get_file_path(workspace, &self.name);
+
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Components grouped by the mode they were running in, in session
+    /// order, and those the workspace no longer has
+    pub fn by_mode(&self, workspace: &Workspace) -> (Vec<(Mode, Vec<String>)>, Vec<String>) {
+        let mut by_mode: Vec<(Mode, Vec<String>)> = Vec::new();
+        let mut missing = Vec::new();
+
+        for (name, component) in &self.components {
+            if !workspace.components.contains_key(name) {
+                missing.push(name.clone());
+                continue;
+            }
+
+            match by_mode.iter_mut().find(|(mode, _)| *mode == component.mode) {
+                Some((_, names)) => names.push(name.clone()),
+                None => by_mode.push((component.mode.clone(), vec![name.clone()])),
+            }
+        }
+
+        (by_mode, missing)
+    }
+
+    /// All saved sessions, ordered by name
+    pub fn list(workspace: &Workspace) -> Result<Vec<Session>, Box<dyn Error>> {
+        let dir = Session::dir(workspace);
+        let mut sessions = Vec::new();
+
+        if !dir.exists() {
+            return Ok(sessions);
+        }
+
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+
+            let content = fs::read_to_string(&path)?;
+            sessions.push(serde_json::from_str::<Session>(&content)?);
+        }
+
+        sessions.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(sessions)
+    }
+}
+
+/// Branch checked out in the component's own repository, if it has one
+pub fn git_branch(component: &Component) -> Option<String> {
+    let path = component.context.as_ref()?.get("SVC_PATH")?;
+
+    // Services living inside the workspace repository are not code components
+    if !Path::new(path).join(".git").exists() {
+        return None;
+    }
+
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .current_dir(path)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    if branch.is_empty() {
+        None
+    } else {
+        Some(branch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        args::{parse_global_options, AppArgs},
+        core::SystemPath,
+        workspace::create_workspace,
+    };
+    use clap::Parser;
+
+    fn workspace(root: &Path) -> Workspace {
+        let config = r#"{
+            "name": "shop",
+            "version": "1.0.0",
+            "variables": {},
+            "components": {
+                "database": { "path": "/srv/database" },
+                "backend": { "path": "/srv/backend" },
+                "worker": { "path": "/srv/worker" }
+            }
+        }"#;
+        let cli = AppArgs::try_parse_from(["uniform", "ps"]).unwrap();
+        let root = root.display().to_string();
+
+        let mut ws = create_workspace(SystemPath::new(&root), &root);
+        ws.config = Some(serde_json::from_str(config).unwrap());
+        ws.init(&parse_global_options(&cli)).unwrap();
+
+        ws
+    }
+
+    fn session(name: &str, components: &[(&str, Mode)]) -> Session {
+        Session {
+            name: name.to_string(),
+            saved_at: 1_700_000_000,
+            components: components
+                .iter()
+                .map(|(name, mode)| {
+                    let component = SessionComponent {
+                        mode: mode.clone(),
+                        branch: None,
+                    };
+                    (name.to_string(), component)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn saves_loads_and_lists_sessions() {
+        let root = std::env::temp_dir().join(format!("uniform-session-{}", std::process::id()));
+        let ws = workspace(&root);
+
+        let empty = Session::list(&ws).unwrap();
+
+        let mut feature = session("feature", &[("backend", Mode::Default)]);
+        feature.components["backend"].branch = Some("feature/cart".to_string());
+        feature.save(&ws).unwrap();
+        session("bugfix", &[("database", Mode::Hook)])
+            .save(&ws)
+            .unwrap();
+        fs::write(Session::dir(&ws).join("notes.txt"), "not a session").unwrap();
+
+        let loaded = Session::load(&ws, "feature");
+        let listed = Session::list(&ws);
+        let missing = Session::load(&ws, "release").map(|_| ());
+        let escaping = Session::load(&ws, "../state").map(|_| ());
+        let unsaved = session("../state", &[]).save(&ws);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(empty.is_empty());
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.saved_at, 1_700_000_000);
+        assert_eq!(loaded.components["backend"].mode, Mode::Default);
+        assert_eq!(
+            loaded.components["backend"].branch.as_deref(),
+            Some("feature/cart")
+        );
+
+        let names: Vec<String> = listed.unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["bugfix", "feature"]);
+
+        assert!(missing.unwrap_err().to_string().contains("not found"));
+        assert!(escaping.is_err());
+        assert!(unsaved.is_err());
+    }
+
+    #[test]
+    fn groups_components_by_mode_in_session_order() {
+        let ws = workspace(Path::new("/srv"));
+        let session = session(
+            "feature",
+            &[
+                ("backend", Mode::Default),
+                ("database", Mode::Hook),
+                ("removed", Mode::Default),
+                ("worker", Mode::Default),
+            ],
+        );
+
+        let (by_mode, missing) = session.by_mode(&ws);
+
+        assert_eq!(
+            by_mode,
+            [
+                (
+                    Mode::Default,
+                    vec!["backend".to_string(), "worker".to_string()]
+                ),
+                (Mode::Hook, vec!["database".to_string()]),
+            ]
+        );
+        assert_eq!(missing, ["removed"]);
+    }
+}
@@ -0,0 +1,73 @@
+use clap::Args;
+use std::error::Error;
+
+use prettytable::{format, Table};
+use time::OffsetDateTime;
+
+use crate::{
+    args::ExecuteTrait, core::GlobalOptions, session::Session, settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct SessionLsCommand {}
+
+impl ExecuteTrait for SessionLsCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let sessions = Session::list(&ws)?;
+
+        if global_options.json {
+            println!("{}", serde_json::to_string_pretty(&sessions)?);
+            return Ok(None);
+        }
+
+        let mut table = Table::new();
+        table.set_titles(row!["Name", "Components", "Saved"]);
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        for session in &sessions {
+            let components = session
+                .components
+                .iter()
+                .map(|(name, component)| match &component.branch {
+                    Some(branch) => format!("{} ({}, {})", name, component.mode, branch),
+                    None => format!("{} ({})", name, component.mode),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            table.add_row(row![
+                session.name,
+                components,
+                format_time(session.saved_at)
+            ]);
+        }
+
+        table.printstd();
+
+        Ok(None)
+    }
+}
+
+fn format_time(timestamp: u64) -> String {
+    match OffsetDateTime::from_unix_timestamp(timestamp as i64) {
+        Ok(time) => format!(
+            "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+            time.year(),
+            time.month() as u8,
+            time.day(),
+            time.hour(),
+            time.minute()
+        ),
+        Err(_) => String::new(),
+    }
+}
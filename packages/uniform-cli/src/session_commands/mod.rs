@@ -0,0 +1,3 @@
+pub mod ls;
+pub mod restore;
+pub mod save;
@@ -0,0 +1,138 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::{confirm, GlobalOptions},
+    plan::Plan,
+    session::{git_branch, Session},
+    settings::Settings,
+    status::WorkspaceStatus,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct SessionRestoreCommand {
+    /// Name of the session
+    name: String,
+}
+
+impl ExecuteTrait for SessionRestoreCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let session = Session::load(&ws, &self.name)?;
+
+        // Components are started in the mode they were running in
+        let (by_mode, missing) = session.by_mode(&ws);
+
+        for name in missing {
+            println!(
+                "{} component {} no longer exists, skipping it",
+                "Warning:".yellow(),
+                name
+            );
+        }
+
+        let mut start_plans = Vec::new();
+
+        for (mode, names) in by_mode {
+            let mut options = global_options.clone();
+            options.mode = Some(mode);
+
+            let targets = names
+                .iter()
+                .map(|name| ws.resolve_target(name))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let plan = Plan::start(&ws, &targets, &options)?;
+            start_plans.push((plan, options));
+        }
+
+        // Everything else that runs now, dependencies of the session excluded
+        let mut others = Vec::new();
+
+        let status = WorkspaceStatus::load(&ws, global_options)?;
+
+        for name in ws.get_executable_component_names() {
+            let component = ws.components.get(&name).unwrap();
+
+            let needed = session.components.contains_key(&name)
+                || start_plans
+                    .iter()
+                    .any(|(plan, _)| plan.steps.iter().any(|step| step.component == name));
+
+            if needed || component.config.hosted_in.is_some() {
+                continue;
+            }
+
+            if status.is_running(&name) {
+                others.push(name);
+            }
+        }
+
+        let stop_targets = others
+            .iter()
+            .map(|name| ws.resolve_target(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let stop_plan = Plan::stop(&ws, &stop_targets, global_options)?;
+
+        if global_options.dry_run {
+            stop_plan.print(global_options)?;
+
+            for (plan, options) in &start_plans {
+                plan.print(options)?;
+            }
+
+            return Ok(None);
+        }
+
+        if !others.is_empty() {
+            let question = format!("Stop {}, not part of the session?", others.join(", "));
+
+            if !confirm(&question, global_options)? {
+                return Err(Box::new(CliError::new(
+                    "Session not restored, pass --force to stop other components without asking",
+                )));
+            }
+
+            stop_plan.execute(&ws, global_options)?;
+        }
+
+        for (plan, options) in &start_plans {
+            plan.execute(&ws, options)?;
+        }
+
+        // Branches are only reported, checking them out could lose work
+        for (name, component) in &session.components {
+            let saved = match &component.branch {
+                Some(branch) => branch,
+                None => continue,
+            };
+
+            let current = ws.components.get(name).and_then(git_branch);
+
+            if current.as_ref() != Some(saved) {
+                println!(
+                    "{} {} is on {}, the session was saved on {}",
+                    "Warning:".yellow(),
+                    name,
+                    current.unwrap_or_else(|| "no branch".to_string()),
+                    saved.bright_green()
+                );
+            }
+        }
+
+        println!("Restored session {}", self.name.bright_green());
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,68 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::{confirm, validate_name, GlobalOptions},
+    session::Session,
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct SessionSaveCommand {
+    /// Name of the session
+    name: String,
+}
+
+impl ExecuteTrait for SessionSaveCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        validate_name("session", &self.name)?;
+
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let session = Session::capture(&ws, &self.name, global_options)?;
+
+        if session.components.is_empty() {
+            return Err(Box::new(CliError::new(
+                "Nothing is running, there is no session to save",
+            )));
+        }
+
+        if Session::get_file_path(&ws, &self.name).exists() {
+            let question = format!("Session {} exists, overwrite it?", self.name);
+
+            if !confirm(&question, global_options)? {
+                return Err(Box::new(CliError::new(
+                    "Session not saved, pass --force to overwrite it",
+                )));
+            }
+        }
+
+        for (name, component) in &session.components {
+            match &component.branch {
+                Some(branch) => {
+                    println!("{} ({}) on {}", name.bright_green(), component.mode, branch)
+                }
+                None => println!("{} ({})", name.bright_green(), component.mode),
+            }
+        }
+
+        if global_options.dry_run {
+            return Ok(None);
+        }
+
+        session.save(&ws)?;
+        println!("Saved session {}", self.name.bright_green());
+
+        Ok(None)
+    }
+}
@@ -107,7 +107,19 @@ impl Settings {
     }
 }
 
+#[cfg(test)]
+thread_local! {
+    /// Home directory settings are kept in for the current test thread,
+    /// so tests running in parallel don't share settings
+    pub static TEST_HOME: std::cell::RefCell<Option<PathBuf>> = const { std::cell::RefCell::new(None) };
+}
+
 fn get_home_dir() -> Option<PathBuf> {
+    #[cfg(test)]
+    if let Some(home) = TEST_HOME.with(|home| home.borrow().clone()) {
+        return Some(home);
+    }
+
     #[cfg(target_os = "windows")]
     {
         std::env::var_os("USERPROFILE").map(PathBuf::from)
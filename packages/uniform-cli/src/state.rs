@@ -0,0 +1,221 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fs::{create_dir_all, File};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{component::Component, core::Mode, workspace::Workspace};
+
+/// What uniform knows about a component it has started
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentState {
+    pub started_at: u64,
+    pub mode: Mode,
+    pub context_hash: String,
+    pub compose_hash: String,
+}
+
+/// Workspace state kept in ${HOME_PATH}/state.json
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct WorkspaceState {
+    pub components: IndexMap<String, ComponentState>,
+}
+
+impl WorkspaceState {
+    pub fn get_file_path(workspace: &Workspace) -> PathBuf {
+        workspace.home_path().join("state.json")
+    }
+
+    // Loads state from file. Missing file means nothing was started yet.
+    pub fn load(workspace: &Workspace) -> Result<WorkspaceState, Box<dyn Error>> {
+        let path = WorkspaceState::get_file_path(workspace);
+
+        if !path.exists() {
+            return Ok(WorkspaceState::default());
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let state = serde_json::from_str(&content)?;
+
+        Ok(state)
+    }
+
+    pub fn save(&self, workspace: &Workspace) -> Result<(), Box<dyn Error>> {
+        let path = WorkspaceState::get_file_path(workspace);
+
+        if let Some(dir) = path.parent() {
+            create_dir_all(dir)?;
+        }
+
+        let mut file = File::create(&path)?;
+        let str = serde_json::to_string_pretty(self)?;
+        file.write_all(str.as_bytes())?;
+
+        Ok(())
+    }
+
+    pub fn record_start(&mut self, component: &Component, mode: &Mode) {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        self.components.insert(
+            component.name.clone(),
+            ComponentState {
+                started_at,
+                mode: mode.clone(),
+                context_hash: context_hash(component),
+                compose_hash: compose_hash(component),
+            },
+        );
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.components.shift_remove(name);
+    }
+
+    /// Component was started with a config that differs from the current one
+    pub fn is_stale(&self, component: &Component) -> bool {
+        match self.components.get(&component.name) {
+            None => false,
+            Some(state) => {
+                state.context_hash != context_hash(component)
+                    || state.compose_hash != compose_hash(component)
+            }
+        }
+    }
+}
+
+/// Hash of the context variables the compose file refers to and the
+/// `COMPOSE_*` ones compose reads itself. Variables only uniform uses, such
+/// as those added by later versions, leave started components up to date.
+pub fn context_hash(component: &Component) -> String {
+    let compose = String::from_utf8_lossy(&compose_content(component)).to_string();
+
+    vars_hash(component.context.as_ref().unwrap(), &compose)
+}
+
+/// Hash of the compose file contents. Unreadable files hash as empty.
+pub fn compose_hash(component: &Component) -> String {
+    to_hex(&Sha256::digest(compose_content(component)))
+}
+
+fn compose_content(component: &Component) -> Vec<u8> {
+    component
+        .context
+        .as_ref()
+        .unwrap()
+        .get("COMPOSE_FILE")
+        .and_then(|path| std::fs::read(path).ok())
+        .unwrap_or_default()
+}
+
+// Independent of variable order
+fn vars_hash(ctx: &IndexMap<String, String>, compose: &str) -> String {
+    let used = referenced_vars(compose);
+    let mut entries: Vec<_> = ctx
+        .iter()
+        .filter(|(k, _)| k.starts_with("COMPOSE_") || used.contains(k.as_str()))
+        .collect();
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+
+    for (k, v) in entries {
+        hasher.update(k.as_bytes());
+        hasher.update(b"=");
+        hasher.update(v.as_bytes());
+        hasher.update(b"\n");
+    }
+
+    to_hex(&hasher.finalize())
+}
+
+/// Names of `$NAME` and `${NAME...}` references, `$$` being an escaped `$`
+fn referenced_vars(content: &str) -> HashSet<&str> {
+    let mut vars = HashSet::new();
+    let mut rest = content;
+
+    while let Some(i) = rest.find('$') {
+        rest = &rest[i + 1..];
+
+        if let Some(after) = rest.strip_prefix('$') {
+            rest = after;
+            continue;
+        }
+
+        let name = rest.strip_prefix('{').unwrap_or(rest);
+        let len = name
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(name.len());
+
+        if len > 0 {
+            vars.insert(&name[..len]);
+        }
+    }
+
+    vars
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPOSE: &str = "services:
+  app:
+    image: ${APP_IMAGE}
+    user: $USER_ID:${GROUP_ID:-1000}
+    command: echo $$NOT_A_VAR
+";
+
+    fn ctx(vars: &[(&str, &str)]) -> IndexMap<String, String> {
+        vars.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_referenced_vars() {
+        let mut vars: Vec<&str> = referenced_vars(COMPOSE).into_iter().collect();
+        vars.sort();
+
+        assert_eq!(vars, ["APP_IMAGE", "GROUP_ID", "USER_ID"]);
+    }
+
+    #[test]
+    fn hash_ignores_vars_compose_does_not_use() {
+        let base = ctx(&[
+            ("APP_IMAGE", "app:1"),
+            ("USER_ID", "1000"),
+            ("COMPOSE_PROJECT_NAME", "ws-app"),
+        ]);
+        let hash = vars_hash(&base, COMPOSE);
+
+        let mut added = base.clone();
+        added.insert("APP_HOSTNAME".to_string(), "app.localhost".to_string());
+        added.insert("NOT_A_VAR".to_string(), "x".to_string());
+        assert_eq!(vars_hash(&added, COMPOSE), hash);
+
+        let mut reordered = ctx(&[("USER_ID", "1000")]);
+        reordered.extend(base.clone());
+        assert_eq!(vars_hash(&reordered, COMPOSE), hash);
+
+        let mut changed = base.clone();
+        changed.insert("APP_IMAGE".to_string(), "app:2".to_string());
+        assert_ne!(vars_hash(&changed, COMPOSE), hash);
+
+        let mut renamed = base;
+        renamed.insert("COMPOSE_PROJECT_NAME".to_string(), "other".to_string());
+        assert_ne!(vars_hash(&renamed, COMPOSE), hash);
+    }
+}
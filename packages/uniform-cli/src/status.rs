@@ -0,0 +1,170 @@
+use std::error::Error;
+
+use indexmap::IndexMap;
+use tokio::runtime::Runtime;
+
+use crate::{
+    core::GlobalOptions,
+    engine::{create_engine, ContainerInfo, Engine, EngineResult},
+    workspace::Workspace,
+};
+
+/// Containers of a workspace grouped by the component that owns them
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceStatus {
+    pub components: IndexMap<String, Vec<ContainerInfo>>,
+}
+
+impl WorkspaceStatus {
+    /// Gathers status of every component with a single engine query
+    pub async fn query(
+        engine: &dyn Engine,
+        workspace: &Workspace,
+    ) -> EngineResult<WorkspaceStatus> {
+        let name = &workspace.config.as_ref().unwrap().name;
+        let containers = engine.list_containers(name).await?;
+
+        Ok(WorkspaceStatus::from_containers(containers))
+    }
+
+    /// Blocking `query` for code outside of an async runtime
+    pub fn load(
+        workspace: &Workspace,
+        options: &GlobalOptions,
+    ) -> Result<WorkspaceStatus, Box<dyn Error>> {
+        let engine = create_engine(options);
+
+        Runtime::new()?
+            .block_on(WorkspaceStatus::query(engine.as_ref(), workspace))
+            .map_err(|err| err as Box<dyn Error>)
+    }
+
+    pub fn from_containers(containers: Vec<ContainerInfo>) -> WorkspaceStatus {
+        let mut status = WorkspaceStatus::default();
+
+        for container in containers {
+            let component = match container.component() {
+                Some(component) => component.to_string(),
+                None => continue,
+            };
+
+            status
+                .components
+                .entry(component)
+                .or_default()
+                .push(container);
+        }
+
+        for containers in status.components.values_mut() {
+            containers.sort_by(|a, b| a.service().cmp(&b.service()));
+        }
+
+        status
+    }
+
+    pub fn containers(&self, component: &str) -> &[ContainerInfo] {
+        self.components
+            .get(component)
+            .map(|containers| containers.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Declared services of a component followed by any other service it has
+    /// containers for, each with its container. Services that never had a
+    /// container still get an entry.
+    pub fn services(
+        &self,
+        component: &str,
+        declared: &[String],
+    ) -> Vec<(String, Option<&ContainerInfo>)> {
+        let containers = self.containers(component);
+
+        let mut services = declared.to_vec();
+        for container in containers {
+            if let Some(service) = container.service() {
+                if !services.iter().any(|s| s == service) {
+                    services.push(service.to_string());
+                }
+            }
+        }
+
+        services
+            .into_iter()
+            .map(|service| {
+                let container = containers
+                    .iter()
+                    .find(|container| container.service() == Some(service.as_str()));
+
+                (service, container)
+            })
+            .collect()
+    }
+
+    pub fn is_running(&self, component: &str) -> bool {
+        self.containers(component).iter().any(|c| c.is_running())
+    }
+
+    /// Running container of a component service
+    pub fn running_container(&self, component: &str, service: &str) -> Option<&ContainerInfo> {
+        self.containers(component)
+            .iter()
+            .find(|c| c.is_running() && c.service() == Some(service))
+    }
+
+    /// Whether one service, or any service without one, of a component is running
+    pub fn is_service_running(&self, component: &str, service: Option<&str>) -> bool {
+        self.containers(component)
+            .iter()
+            .filter(|c| service.is_none() || c.service() == service)
+            .any(|c| c.is_running())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_engine::FakeEngine;
+
+    fn status(engine: &FakeEngine, workspace: &str) -> WorkspaceStatus {
+        let containers = Runtime::new()
+            .unwrap()
+            .block_on(engine.list_containers(workspace))
+            .unwrap();
+
+        WorkspaceStatus::from_containers(containers)
+    }
+
+    #[test]
+    fn groups_containers_of_the_workspace() {
+        let engine = FakeEngine::new();
+        engine.add_container("shop", "backend", "worker", false);
+        engine.add_container("shop", "backend", "app", true);
+        engine.add_container("blog", "backend", "app", true);
+
+        let status = status(&engine, "shop");
+
+        assert_eq!(status.containers("backend").len(), 2);
+        assert_eq!(status.containers("backend")[0].service(), Some("app"));
+        assert!(status.is_running("backend"));
+        assert!(!status.is_running("database"));
+    }
+
+    #[test]
+    fn services_include_declared_and_seen_ones() {
+        let engine = FakeEngine::new();
+        engine.add_container("shop", "backend", "worker", true);
+
+        let status = status(&engine, "shop");
+        let declared = vec!["app".to_string(), "worker".to_string()];
+        let services = status.services("backend", &declared[..1]);
+
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[0].0, "app");
+        assert!(services[0].1.is_none());
+        assert_eq!(services[1].0, "worker");
+        assert!(services[1].1.unwrap().is_running());
+
+        assert_eq!(status.services("backend", &declared).len(), 2);
+        assert!(status.services("database", &[]).is_empty());
+    }
+}
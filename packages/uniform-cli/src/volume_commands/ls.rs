@@ -0,0 +1,56 @@
+use clap::Args;
+use std::error::Error;
+
+use prettytable::{format, Table};
+
+use crate::{
+    args::ExecuteTrait,
+    core::GlobalOptions,
+    resources::{declared_volumes, list_volumes, volume_component},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct VolumeLsCommand {}
+
+impl ExecuteTrait for VolumeLsCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let declared = declared_volumes(&ws);
+        let volumes = list_volumes(&ws)?;
+
+        let mut table = Table::new();
+        table.set_titles(row!["Name", "Component", "Driver", "Status"]);
+        table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
+
+        for (name, config) in &declared {
+            if !volumes.iter().any(|volume| volume.name == *name) {
+                table.add_row(row![
+                    name,
+                    config.component.clone().unwrap_or_default(),
+                    config.driver.clone().unwrap_or_default(),
+                    "Missing"
+                ]);
+            }
+        }
+
+        for volume in &volumes {
+            let component = volume_component(&ws, volume).unwrap_or_default();
+
+            table.add_row(row![volume.name, component, volume.driver, "Created"]);
+        }
+
+        table.printstd();
+
+        Ok(None)
+    }
+}
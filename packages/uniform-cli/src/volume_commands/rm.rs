@@ -0,0 +1,76 @@
+use clap::Args;
+use colored::Colorize;
+use std::error::Error;
+
+use crate::{
+    args::{CliError, ExecuteTrait},
+    core::GlobalOptions,
+    resources::{list_volumes, remove_volume, volume_component},
+    settings::Settings,
+    workspace::create_workspace,
+};
+
+#[derive(Debug, Args)]
+pub struct VolumeRmCommand {
+    /// Volumes to remove
+    #[clap(required_unless_present = "component")]
+    names: Vec<String>,
+
+    /// Remove every volume of this component
+    #[arg(short, long)]
+    component: Option<String>,
+}
+
+impl ExecuteTrait for VolumeRmCommand {
+    fn execute(&self, global_options: &GlobalOptions) -> Result<Option<String>, Box<dyn Error>> {
+        let settings = Settings::load_from_file()?;
+
+        let cwd = std::env::current_dir()?.display().to_string();
+        let path = settings.get_active_project_path()?;
+
+        let mut ws = create_workspace(path, &cwd);
+        ws.load()?;
+        ws.init(global_options)?;
+
+        let volumes = list_volumes(&ws)?;
+        let mut names = self.names.clone();
+
+        if let Some(component) = &self.component {
+            let component = ws.resolve_name(component)?;
+
+            names.extend(
+                volumes
+                    .iter()
+                    .filter(|volume| volume_component(&ws, volume).as_ref() == Some(&component))
+                    .map(|volume| volume.name.clone()),
+            );
+        }
+
+        // Only volumes of this workspace can be removed from here
+        for name in &names {
+            if !volumes.iter().any(|volume| volume.name == *name) {
+                return Err(Box::new(CliError::new(format!(
+                    "Volume {} does not belong to workspace {}",
+                    name,
+                    ws.config.as_ref().unwrap().name
+                ))));
+            }
+        }
+
+        if names.is_empty() {
+            println!("No volumes to remove");
+        }
+
+        for name in names {
+            if global_options.dry_run {
+                println!("Would remove volume {}", name);
+                continue;
+            }
+
+            remove_volume(&name)?;
+            println!("Removed volume {}", name.bright_green());
+        }
+
+        Ok(None)
+    }
+}
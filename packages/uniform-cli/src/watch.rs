@@ -0,0 +1,416 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use colored::Colorize;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use indexmap::IndexMap;
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::{
+    args::CliError,
+    component::Component,
+    component_config::{WatchAction, WatchConfig},
+    core::{exec_shell_piped, subst_known_vars, subst_vars, GlobalOptions},
+    script::ScriptRunner,
+    workspace::Workspace,
+};
+
+const DEFAULT_DEBOUNCE: u64 = 500;
+
+/// How often pending actions are checked while no events arrive
+const TICK: Duration = Duration::from_millis(100);
+
+/// Watch section of a component with compiled globs
+pub struct WatchTarget {
+    pub component: String,
+    pub root: PathBuf,
+    pub config: WatchConfig,
+    include: GlobSet,
+    ignore: GlobSet,
+    debounce: Duration,
+}
+
+impl WatchTarget {
+    /// The component's watch section, or that of its template
+    pub fn new(
+        workspace: &Workspace,
+        component: &Component,
+    ) -> Result<Option<WatchTarget>, Box<dyn Error>> {
+        let config = match component
+            .config
+            .watch
+            .clone()
+            .or_else(|| component.template.as_ref()?.watch.clone())
+        {
+            Some(config) => config,
+            None => return Ok(None),
+        };
+
+        let ctx = component.context.as_ref().unwrap();
+        let root = ctx
+            .get("SVC_PATH")
+            .ok_or_else(|| format!("Component \"{}\" has no path to watch", component.name))?;
+
+        if config.paths.is_empty() {
+            return Err(Box::new(CliError::new(format!(
+                "Watch section of \"{}\" has no paths",
+                component.name
+            ))));
+        }
+
+        match &config.action {
+            WatchAction::Named(name) if name != "restart" => {
+                return Err(Box::new(CliError::new(format!(
+                    "Unknown watch action \"{}\" of \"{}\", use \"restart\", {{\"exec\": ...}} or {{\"script\": ...}}",
+                    name, component.name
+                ))))
+            }
+            WatchAction::Script { script } => {
+                let scripts = &workspace.config.as_ref().unwrap().scripts;
+
+                if !scripts.contains_key(script) {
+                    return Err(Box::new(CliError::new(format!(
+                        "Watch action of \"{}\" runs unknown script \"{}\"",
+                        component.name, script
+                    ))));
+                }
+            }
+            _ => {}
+        }
+
+        WatchTarget::compile(&component.name, Path::new(root), config, ctx).map(Some)
+    }
+
+    fn compile(
+        component: &str,
+        root: &Path,
+        config: WatchConfig,
+        ctx: &IndexMap<String, String>,
+    ) -> Result<WatchTarget, Box<dyn Error>> {
+        // Events carry canonical paths, a symlinked root would never match.
+        // Components that are not checked out are skipped by the caller.
+        let root = if root.is_dir() {
+            root.canonicalize().map_err(|err| {
+                CliError::new(format!(
+                    "Cannot watch {} of \"{}\": {}",
+                    root.display(),
+                    component,
+                    err
+                ))
+            })?
+        } else {
+            root.to_path_buf()
+        };
+
+        let include = glob_set(&config.paths, ctx)?;
+        let ignore = glob_set(config.ignore.as_deref().unwrap_or_default(), ctx)?;
+        let debounce = Duration::from_millis(config.debounce.unwrap_or(DEFAULT_DEBOUNCE));
+
+        Ok(WatchTarget {
+            component: component.to_string(),
+            root,
+            config,
+            include,
+            ignore,
+            debounce,
+        })
+    }
+
+    /// Path relative to the component path, if a change of it triggers the action
+    fn matches(&self, path: &Path) -> Option<PathBuf> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+
+        if self.include.is_match(relative) && !self.ignore.is_match(relative) {
+            Some(relative.to_path_buf())
+        } else {
+            None
+        }
+    }
+
+    pub fn action_label(&self) -> String {
+        match &self.config.action {
+            WatchAction::Named(name) => name.clone(),
+            WatchAction::Exec { exec, .. } => format!("exec {}", exec),
+            WatchAction::Script { script } => format!("script {}", script),
+        }
+    }
+}
+
+/// Changes collected for a target until its debounce runs out
+struct Pending {
+    deadline: Instant,
+    paths: Vec<PathBuf>,
+}
+
+/// Pending changes by target index
+#[derive(Default)]
+struct Debouncer {
+    pending: HashMap<usize, Pending>,
+}
+
+impl Debouncer {
+    /// Records a change, pushing the deadline of the target back
+    fn record(&mut self, index: usize, path: PathBuf, deadline: Instant) {
+        let entry = self.pending.entry(index).or_insert_with(|| Pending {
+            deadline,
+            paths: Vec::new(),
+        });
+        entry.deadline = deadline;
+
+        if !entry.paths.contains(&path) {
+            entry.paths.push(path);
+        }
+    }
+
+    /// Targets that saw no change until their deadline, with the changed paths
+    fn take_due(&mut self, now: Instant) -> Vec<(usize, Vec<PathBuf>)> {
+        let mut due: Vec<usize> = self
+            .pending
+            .iter()
+            .filter(|(_, entry)| entry.deadline <= now)
+            .map(|(index, _)| *index)
+            .collect();
+        due.sort();
+
+        due.into_iter()
+            .map(|index| (index, self.pending.remove(&index).unwrap().paths))
+            .collect()
+    }
+}
+
+/// Follows the targets until interrupted, running the action of a target
+/// once its files stop changing for the debounce time
+pub fn watch(
+    workspace: &Workspace,
+    targets: &[WatchTarget],
+    options: &GlobalOptions,
+) -> Result<(), Box<dyn Error>> {
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+
+    let roots: HashSet<&PathBuf> = targets.iter().map(|target| &target.root).collect();
+    for root in roots {
+        watcher.watch(root, RecursiveMode::Recursive)?;
+    }
+
+    let mut debouncer = Debouncer::default();
+
+    loop {
+        match rx.recv_timeout(TICK) {
+            Ok(Ok(event)) => {
+                if !matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    continue;
+                }
+
+                for (index, target) in targets.iter().enumerate() {
+                    for path in &event.paths {
+                        let relative = match target.matches(path) {
+                            Some(relative) => relative,
+                            None => continue,
+                        };
+
+                        debouncer.record(index, relative, Instant::now() + target.debounce);
+                    }
+                }
+            }
+            Ok(Err(err)) => println!("{} {}", "Warning:".yellow(), err),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(Box::new(CliError::new("File watcher stopped unexpectedly")))
+            }
+        }
+
+        for (index, paths) in debouncer.take_due(Instant::now()) {
+            let target = &targets[index];
+
+            println!(
+                "{} changed: {}",
+                target.component.bright_green(),
+                describe_paths(&paths)
+            );
+
+            if let Err(err) = run_action(workspace, target, options) {
+                println!(
+                    "{} {} of \"{}\" failed: {}",
+                    "Warning:".yellow(),
+                    target.action_label(),
+                    target.component,
+                    err
+                );
+            }
+        }
+    }
+}
+
+fn run_action(
+    workspace: &Workspace,
+    target: &WatchTarget,
+    options: &GlobalOptions,
+) -> Result<(), Box<dyn Error>> {
+    let component = workspace.components.get(&target.component).unwrap();
+
+    match &target.config.action {
+        WatchAction::Named(_) => {
+            // Hosted components are restarted with their host
+            let component = component.get_host(workspace)?.unwrap_or(component);
+
+            println!("{} restart", component.name.bright_green());
+            component.stop(workspace, options)?;
+            component.start(workspace, options)?;
+
+            Ok(())
+        }
+        WatchAction::Exec { exec, service } => {
+            let ctx = component.context.as_ref().unwrap();
+            let exec = subst_known_vars(exec, ctx);
+            let service = service.as_ref().map(|service| subst_vars(service, ctx));
+
+            println!("{} > {}", component.name.bright_green(), exec);
+
+            let (command, env) = component.hook_exec_command(workspace, &exec, service, options)?;
+
+            if options.debug || options.dry_run {
+                println!(">> {}", command);
+            }
+
+            if options.dry_run {
+                return Ok(());
+            }
+
+            exec_shell_piped(&command, &workspace.cwd, &env, None, None)
+        }
+        WatchAction::Script { script } => {
+            println!("{} run {}", component.name.bright_green(), script);
+
+            ScriptRunner::new(workspace, options).run(script, &[])
+        }
+    }
+}
+
+fn glob_set(
+    patterns: &[String],
+    ctx: &IndexMap<String, String>,
+) -> Result<GlobSet, Box<dyn Error>> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let pattern = subst_vars(pattern, ctx);
+        let glob = GlobBuilder::new(&pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| CliError::new(format!("Invalid watch glob: {}", err)))?;
+
+        builder.add(glob);
+    }
+
+    Ok(builder.build()?)
+}
+
+fn describe_paths(paths: &[PathBuf]) -> String {
+    let shown: Vec<String> = paths
+        .iter()
+        .take(3)
+        .map(|path| path.display().to_string())
+        .collect();
+
+    if paths.len() > shown.len() {
+        format!(
+            "{} and {} more",
+            shown.join(", "),
+            paths.len() - shown.len()
+        )
+    } else {
+        shown.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(root: &Path, paths: &[&str], ignore: &[&str]) -> WatchTarget {
+        let config = WatchConfig {
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            ignore: Some(ignore.iter().map(|p| p.to_string()).collect()),
+            debounce: None,
+            action: WatchAction::Named("restart".to_string()),
+        };
+
+        WatchTarget::compile("app", root, config, &IndexMap::new()).unwrap()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn matches_globs_under_the_canonical_root() {
+        let dir = std::env::temp_dir().join(format!("uniform-watch-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("real/src")).unwrap();
+        let link = dir.join("link");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(dir.join("real"), &link).unwrap();
+
+        let target = target(&link, &["src/**/*.rs", "Cargo.toml"], &["src/gen/**"]);
+        let root = dir.join("real").canonicalize().unwrap();
+
+        assert_eq!(
+            target.matches(&root.join("src/main.rs")),
+            Some(PathBuf::from("src/main.rs"))
+        );
+        assert_eq!(
+            target.matches(&root.join("src/a/b.rs")),
+            Some(PathBuf::from("src/a/b.rs"))
+        );
+        assert!(target.matches(&root.join("Cargo.toml")).is_some());
+        assert!(target.matches(&root.join("src/gen/api.rs")).is_none());
+        assert!(target.matches(&root.join("src/notes.md")).is_none());
+        // Separators are literal, top-level globs stay at the top
+        assert!(target.matches(&root.join("sub/Cargo.toml")).is_none());
+        assert!(target.matches(&dir.join("other/src/main.rs")).is_none());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn missing_root_is_kept_for_the_caller_to_skip() {
+        let root = std::env::temp_dir().join("uniform-watch-missing");
+        let target = target(&root, &["**"], &[]);
+
+        assert_eq!(target.root, root);
+        assert!(!target.root.is_dir());
+    }
+
+    #[test]
+    fn debounce_waits_for_changes_to_settle() {
+        let start = Instant::now();
+        let debounce = Duration::from_millis(500);
+        let mut debouncer = Debouncer::default();
+
+        debouncer.record(1, PathBuf::from("a.rs"), start + debounce);
+        debouncer.record(0, PathBuf::from("b.rs"), start + debounce);
+        // A later change of the same target moves its deadline
+        let later = start + Duration::from_millis(300);
+        debouncer.record(1, PathBuf::from("a.rs"), later + debounce);
+        debouncer.record(1, PathBuf::from("c.rs"), later + debounce);
+
+        assert!(debouncer
+            .take_due(start + Duration::from_millis(499))
+            .is_empty());
+        assert_eq!(
+            debouncer.take_due(start + debounce),
+            vec![(0, vec![PathBuf::from("b.rs")])]
+        );
+        assert!(debouncer
+            .take_due(later + Duration::from_millis(499))
+            .is_empty());
+        assert_eq!(
+            debouncer.take_due(later + debounce),
+            vec![(1, vec![PathBuf::from("a.rs"), PathBuf::from("c.rs")])]
+        );
+        assert!(debouncer.take_due(later + debounce * 10).is_empty());
+    }
+}
@@ -3,19 +3,28 @@ use std::{
     collections::HashMap,
     env::join_paths,
     error::Error,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use colored::Colorize;
 use indexmap::IndexMap;
 
 use crate::{
+    args::CliError,
     component::Component,
     component_config::ComponentConfig,
     core::{subst_vars, GlobalOptions, SystemPath},
     workspace_config::{load_workspace_config, WorkspaceConfig},
 };
 
+/// Component given on the command line, optionally narrowed down to one
+/// of its compose services with `component:service`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Target {
+    pub component: String,
+    pub service: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Workspace {
     pub aliases: HashMap<String, String>,
@@ -43,6 +52,23 @@ impl Workspace {
             }
         }
 
+        if !ctx.contains_key("HOME_PATH") {
+            let home_path = subst_vars("${WORKSPACE_PATH}/.uniform", &ctx);
+            ctx.insert("HOME_PATH".to_string(), home_path);
+        }
+
+        // Issued by `uniform certs issue`
+        for (key, file) in [
+            ("TLS_CERT", "cert.pem"),
+            ("TLS_KEY", "key.pem"),
+            ("TLS_CA", "ca.pem"),
+        ] {
+            if !ctx.contains_key(key) {
+                let path = subst_vars(&format!("${{HOME_PATH}}/certs/{}", file), &ctx);
+                ctx.insert(key.to_string(), path);
+            }
+        }
+
         return ctx;
     }
 
@@ -101,9 +127,166 @@ impl Workspace {
 
         self.components = new_components;
 
+        self.aliases = self.collect_aliases()?;
+        self.check_hosting()?;
+
+        Ok(())
+    }
+
+    /// Hosts must be existing components that are not hosted themselves,
+    /// which also rules out components hosting each other
+    fn check_hosting(&self) -> Result<(), Box<dyn Error>> {
+        for (name, component) in self.components.iter() {
+            let host_name = match &component.config.hosted_in {
+                Some(host_name) => host_name,
+                None => continue,
+            };
+
+            if host_name == name {
+                return Err(Box::new(CliError::new(format!(
+                    "Component \"{}\" is hosted in itself",
+                    name
+                ))));
+            }
+
+            let host = self.components.get(host_name).ok_or_else(|| {
+                CliError::new(format!(
+                    "Component \"{}\" is hosted in unknown component \"{}\"",
+                    name, host_name
+                ))
+            })?;
+
+            match &host.config.hosted_in {
+                Some(other) if other == name => {
+                    return Err(Box::new(CliError::new(format!(
+                        "Components \"{}\" and \"{}\" are hosted in each other",
+                        name, host_name
+                    ))))
+                }
+                Some(other) => {
+                    return Err(Box::new(CliError::new(format!(
+                        "Component \"{}\" is hosted in \"{}\", which is hosted in \"{}\" itself; host it in \"{}\" directly",
+                        name, host_name, other, other
+                    ))))
+                }
+                None => {}
+            }
+        }
+
         Ok(())
     }
 
+    fn collect_aliases(&self) -> Result<HashMap<String, String>, Box<dyn Error>> {
+        let mut aliases = HashMap::new();
+
+        for (name, component) in self.components.iter() {
+            let alias = match &component.config.alias {
+                Some(alias) => alias,
+                None => continue,
+            };
+
+            if self.components.contains_key(alias) {
+                return Err(Box::new(CliError::new(format!(
+                    "Alias \"{}\" of component \"{}\" collides with a component name",
+                    alias, name
+                ))));
+            }
+
+            if let Some(other) = aliases.insert(alias.clone(), name.clone()) {
+                return Err(Box::new(CliError::new(format!(
+                    "Alias \"{}\" is defined for both \"{}\" and \"{}\"",
+                    alias, other, name
+                ))));
+            }
+        }
+
+        Ok(aliases)
+    }
+
+    /// Resolves `component[:service]` given on the command line
+    pub fn resolve_target(&self, target: &str) -> Result<Target, Box<dyn Error>> {
+        let (name, service) = match target.split_once(':') {
+            Some((name, service)) => (name, Some(service.to_string())),
+            None => (target, None),
+        };
+
+        let component = self.resolve_name(name)?;
+
+        if let Some(service) = &service {
+            let services = self.components[&component]
+                .get_services()
+                .unwrap_or_default();
+
+            if !services.is_empty() && !services.contains(service) {
+                return Err(Box::new(CliError::new(format!(
+                    "Unknown service \"{}\" in component \"{}\". Possible services are: {}",
+                    service,
+                    component,
+                    services.join(", ")
+                ))));
+            }
+        }
+
+        Ok(Target { component, service })
+    }
+
+    /// Resolves a component name or alias given on the command line
+    pub fn resolve_name(&self, name: &str) -> Result<String, Box<dyn Error>> {
+        if self.components.contains_key(name) {
+            return Ok(name.to_string());
+        }
+
+        if let Some(target) = self.aliases.get(name) {
+            return Ok(target.clone());
+        }
+
+        let names = self.get_executable_component_names();
+        let mut candidates: Vec<(usize, &str)> = names
+            .iter()
+            .chain(self.aliases.keys())
+            .map(|candidate| (strsim::levenshtein(name, candidate), candidate.as_str()))
+            .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(2))
+            .collect();
+        candidates.sort();
+
+        if candidates.is_empty() {
+            return Err(Box::new(CliError::new(format!(
+                "Unknown component \"{}\". Possible components are: {}",
+                name,
+                self.get_executable_component_names().join(", ")
+            ))));
+        }
+
+        Err(Box::new(CliError::new(format!(
+            "Unknown component \"{}\". Did you mean: {}?",
+            name,
+            candidates
+                .iter()
+                .take(3)
+                .map(|(_, candidate)| *candidate)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))))
+    }
+
+    /// Variables of the workspace, or those of a component when one is given
+    pub fn get_context(
+        &self,
+        component: Option<&str>,
+    ) -> Result<IndexMap<String, String>, Box<dyn Error>> {
+        let name = match component {
+            Some(name) => self.resolve_name(name)?,
+            None => return Ok(self.context.clone()),
+        };
+
+        Ok(self.components.get(&name).unwrap().context.clone().unwrap_or_default())
+    }
+
+    /// Directory for files uniform keeps per workspace (state, sessions, etc.)
+    pub fn home_path(&self) -> PathBuf {
+        PathBuf::from(self.context.get("HOME_PATH").unwrap())
+    }
+
     /// finds Component that is not a template
     pub fn find_executable_component(&self, name: &str) -> Option<Box<&Component>> {
         let comp = self.components.get(name);
@@ -132,3 +315,146 @@ pub fn create_workspace(ws_path: SystemPath, cwd: &str) -> Workspace {
         config: None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::{parse_global_options, AppArgs};
+    use clap::Parser;
+
+    fn workspace(components: &str) -> Result<Workspace, Box<dyn Error>> {
+        let config = format!(
+            r#"{{ "name": "shop", "version": "1.0.0", "variables": {{}}, "components": {} }}"#,
+            components
+        );
+        let cli = AppArgs::try_parse_from(["uniform", "ps"]).unwrap();
+
+        let mut ws = create_workspace(SystemPath::new("/srv"), "/srv");
+        ws.config = Some(serde_json::from_str(&config)?);
+        ws.init(&parse_global_options(&cli))?;
+
+        Ok(ws)
+    }
+
+    fn error(components: &str) -> String {
+        workspace(components).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn resolves_names_and_aliases() {
+        let ws = workspace(
+            r#"{
+                "postgres": { "path": "/srv/postgres", "alias": "pg" },
+                "redis": { "path": "/srv/redis" }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(ws.resolve_name("pg").unwrap(), "postgres");
+        assert_eq!(ws.resolve_name("redis").unwrap(), "redis");
+    }
+
+    #[test]
+    fn rejects_alias_colliding_with_a_component() {
+        let err = error(
+            r#"{
+                "postgres": { "path": "/srv/postgres", "alias": "redis" },
+                "redis": { "path": "/srv/redis" }
+            }"#,
+        );
+
+        assert!(err.contains("Alias \"redis\" of component \"postgres\" collides"));
+    }
+
+    #[test]
+    fn rejects_duplicate_alias() {
+        let err = error(
+            r#"{
+                "postgres": { "path": "/srv/postgres", "alias": "pg" },
+                "postgis": { "path": "/srv/postgis", "alias": "pg" }
+            }"#,
+        );
+
+        assert!(err.contains("Alias \"pg\" is defined for both \"postgres\" and \"postgis\""));
+    }
+
+    #[test]
+    fn suggests_closest_names_first() {
+        let ws = workspace(
+            r#"{
+                "node": { "path": "/srv/node", "isTemplate": true },
+                "postgis": { "path": "/srv/postgis" },
+                "postgres": { "path": "/srv/postgres", "alias": "pg" },
+                "redis": { "path": "/srv/redis" }
+            }"#,
+        )
+        .unwrap();
+
+        let err = ws.resolve_name("postgre").unwrap_err().to_string();
+        assert!(err.ends_with("Did you mean: postgres, postgis?"));
+
+        // Aliases are suggested too
+        let err = ws.resolve_name("pq").unwrap_err().to_string();
+        assert!(err.ends_with("Did you mean: pg?"));
+
+        // Templates are not
+        let err = ws.resolve_name("nod").unwrap_err().to_string();
+        assert!(!err.contains("node"));
+
+        // Nothing within the threshold lists every component instead
+        let err = ws.resolve_name("mysql").unwrap_err().to_string();
+        assert!(err.ends_with("Possible components are: postgis, postgres, redis"));
+    }
+
+    #[test]
+    fn accepts_components_hosted_in_a_plain_host() {
+        let ws = workspace(
+            r#"{
+                "php": { "path": "/srv/php" },
+                "admin": { "path": "/srv/admin", "hosted_in": "php" },
+                "shop": { "path": "/srv/shop", "hosted_in": "php" }
+            }"#,
+        );
+
+        assert!(ws.is_ok());
+    }
+
+    #[test]
+    fn rejects_component_hosted_in_itself() {
+        let err = error(r#"{ "php": { "path": "/srv/php", "hosted_in": "php" } }"#);
+
+        assert!(err.contains("Component \"php\" is hosted in itself"));
+    }
+
+    #[test]
+    fn rejects_components_hosted_in_each_other() {
+        let err = error(
+            r#"{
+                "admin": { "path": "/srv/admin", "hosted_in": "php" },
+                "php": { "path": "/srv/php", "hosted_in": "admin" }
+            }"#,
+        );
+
+        assert!(err.contains("Components \"admin\" and \"php\" are hosted in each other"));
+    }
+
+    #[test]
+    fn rejects_chained_hosting() {
+        let err = error(
+            r#"{
+                "admin": { "path": "/srv/admin", "hosted_in": "php" },
+                "php": { "path": "/srv/php", "hosted_in": "nginx" },
+                "nginx": { "path": "/srv/nginx" }
+            }"#,
+        );
+
+        assert!(err.contains("host it in \"nginx\" directly"));
+    }
+
+    #[test]
+    fn rejects_unknown_host() {
+        let err = error(r#"{ "admin": { "path": "/srv/admin", "hosted_in": "php" } }"#);
+
+        assert!(err.contains("hosted in unknown component \"php\""));
+    }
+}
@@ -13,7 +13,48 @@ pub struct WorkspaceConfig {
     pub version: String,
     pub variables: IndexMap<String, String>,
     pub components: IndexMap<String, ComponentConfig>,
-    // TODO: packages
+    /// Networks created before any component starts, names support variables
+    #[serde(default)]
+    pub networks: IndexMap<String, NetworkConfig>,
+    /// Named volumes created before any component starts
+    #[serde(default)]
+    pub volumes: IndexMap<String, VolumeConfig>,
+    /// Named lists of steps run with `uniform run`
+    #[serde(default)]
+    pub scripts: IndexMap<String, Vec<ScriptStep>>,
+    /// Libraries developed alongside the components, not run by uniform
+    #[serde(default)]
+    pub packages: IndexMap<String, PackageConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PackageConfig {
+    pub path: String,
+    pub repository: Option<String>,
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NetworkConfig {
+    pub driver: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct VolumeConfig {
+    pub driver: Option<String>,
+    /// Component the volume holds data of, removed with it on `down --volumes`
+    pub component: Option<String>,
+}
+
+/// Step of a workspace script, a plain string is a host shell command
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum ScriptStep {
+    Shell(String),
+    /// Uniform subcommand line, e.g. `start database`
+    Uniform { uniform: String },
+    /// Another script of the workspace, followed by its arguments
+    Script { script: String },
 }
 
 impl WorkspaceConfig {
@@ -36,6 +77,22 @@ impl WorkspaceConfig {
             result.variables.insert(k.clone(), v.clone());
         }
 
+        for (k, v) in &other.networks {
+            result.networks.insert(k.clone(), v.clone());
+        }
+
+        for (k, v) in &other.volumes {
+            result.volumes.insert(k.clone(), v.clone());
+        }
+
+        for (k, v) in &other.scripts {
+            result.scripts.insert(k.clone(), v.clone());
+        }
+
+        for (k, v) in &other.packages {
+            result.packages.insert(k.clone(), v.clone());
+        }
+
         return result;
     }
 }